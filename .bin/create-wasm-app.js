@@ -1,24 +1,263 @@
 #!/usr/bin/env node
 
-const { spawn } = require("child_process");
 const fs = require("fs");
+const path = require("path");
+const { parseArgs, readOptionsFile } = require("../lib/args");
+const { diagnose } = require("../lib/doctor");
+const { initRepository } = require("../lib/git");
+const { layersFor, resolveOptions, varsFor } = require("../lib/options");
+const {
+  configurePackageManager,
+  detectPackageManager,
+  runPackageManager,
+  runScript,
+  scriptCommand,
+} = require("../lib/package-manager");
+const { personalize } = require("../lib/project");
+const { promptOptions } = require("../lib/prompt");
+const { BUNDLERS, installProject, scaffold, withTempDir } = require("../lib/template");
+const {
+  MARKER,
+  applyUpgrade,
+  planUpgrade,
+  recordPackage,
+  writeMarker,
+} = require("../lib/upgrade");
+const { addPackage, isLocal, pinPackage, usePackage } = require("../lib/wasm-package");
 
-let folderName = '.';
+const USAGE = `Usage: create-wasm-app [folder] [options]
+       create-wasm-app <command> [options]
 
-if (process.argv.length >= 3) {
-  folderName = process.argv[2];
-  if (!fs.existsSync(folderName)) {
-    fs.mkdirSync(folderName);
+Run in a terminal, create-wasm-app asks for the options that aren't given.
+
+Commands:
+  add <pkg>     install a wasm-pack package (an npm package or the path to a
+                \`pkg/\` directory, like --pkg) in the project in the current
+                directory, and import it in its index.js or index.ts
+  doctor [folder]
+                check the tools needed to build the project in \`folder\`
+                (default: the current directory)
+  upgrade [folder] [--dry-run]
+                merge the changes made to the template since the project in
+                \`folder\` was created into it, after showing them
+
+Options:
+  --bundler <name>
+                bundler to build and serve the app with: ${BUNDLERS.join(", ")}
+                (default: ${BUNDLERS[0]})
+  --config <file>
+                read options from a JSON file instead of asking for them,
+                e.g. \`{ "bundler": "vite", "with-crate": true }\`
+  --force       create the project in a folder that already has some of its
+                files, overwriting them
+  --from-git    clone the latest template from GitHub instead of using the
+                copy shipped with this package
+  --merge       create the project in a folder that already has some of its
+                files, only adding the missing ones
+  --name <name> package name for the project, defaults to the folder name
+  --pkg <pkg>   wasm-pack package to use instead of hello-wasm-pack, either
+                an npm package (\`name\` or \`name@range\`) or the path to a
+                wasm-pack \`pkg/\` directory
+  --pwa         make the app an installable Progressive Web App, with a web
+                app manifest and a service worker that keeps it working
+                offline
+  --simd        with --with-crate, also build the crate with SIMD
+                instructions and load that build on browsers supporting it
+  --threads     with --with-crate, build the crate with wasm threads on
+                nightly Rust and start a rayon thread pool for it
+  --typescript  write the app in TypeScript
+  --with-crate  add a Rust crate in \`crate/\` and use its wasm-pack output
+                instead of an npm package
+  --worker      run the wasm package in a Web Worker, calling it from the
+                page through promise-returning functions
+  --yes         don't ask for options on a terminal, use the defaults
+  --no-git      don't create a git repository for the new project
+  --no-install  don't install the new project's dependencies (with the
+                package manager running this command: npm, yarn or pnpm)
+  --help        print this message
+
+To create a project in a folder named like a command, write it as a path,
+e.g. \`./doctor\`.`;
+
+const STATUS_MARKS = { ok: "✔", warn: "!", fail: "✖" };
+
+// Options for `create` come from the command line, then the `--config` file,
+// then the answers to prompts on a terminal. Answers are options like the
+// others, so any way of giving the same ones makes the same project.
+async function create(positionals, flags) {
+  let answers = flags;
+  if (flags.config) {
+    answers = { ...readOptionsFile(flags.config, CONFIG_OPTIONS), ...flags };
+  } else if (!flags.yes && process.stdin.isTTY && process.stdout.isTTY) {
+    answers = await promptOptions(positionals[0] || ".", flags);
+  }
+
+  const options = resolveOptions(positionals, answers);
+  const folderName = options.folder;
+  const vars = varsFor(options);
+  // In worker mode, the app calls the package through wasm-proxy.js, which
+  // is generated from whatever package the project depends on.
+  const entry = options.worker ? null : `index.${vars.scriptExt}`;
+
+  // The project is put together in a temporary directory, so that nothing
+  // is written when it would replace existing files.
+  withTempDir(staging => {
+    scaffold(staging, {
+      fromGit: options.fromGit,
+      layers: layersFor(options),
+      vars,
+    });
+    personalize(staging, { name: options.name });
+    const pkg = options.pkg && usePackage(staging, options.pkg, { entry, projectDir: folderName });
+    writeMarker(staging, options, pkg || null);
+
+    fs.mkdirSync(folderName, { recursive: true });
+    installProject(staging, folderName, { existing: options.existing });
+  });
+
+  // The crate's package has to be built before the dependency on it can be
+  // installed.
+  const manager = detectPackageManager(folderName);
+  configurePackageManager(manager, folderName);
+  let installed = false;
+  if (options.install) {
+    try {
+      if (options.withCrate) {
+        runScript(manager, folderName, "build:wasm");
+      }
+      runPackageManager(manager, "install", folderName);
+      if (options.pkg && !isLocal(options.pkg)) {
+        recordPackage(folderName, pinPackage(folderName, options.pkg, { entry, manager }));
+      }
+      installed = true;
+    } catch (e) {
+      console.warn(`Skipped installing the dependencies: ${e.message}`);
+    }
   }
+
+  // After installing, so that the first commit has the lockfile.
+  if (options.git) {
+    const skipped = initRepository(folderName);
+    if (skipped) {
+      console.warn(`Skipped creating a git repository: ${skipped}`);
+    }
+  }
+
+  const steps = [];
+  if (path.resolve(folderName) !== process.cwd()) {
+    steps.push(`cd ${folderName}`);
+  }
+  if (!installed) {
+    if (options.withCrate) {
+      steps.push(scriptCommand(manager, "build:wasm"));
+    }
+    steps.push(`${manager} install`);
+  }
+  steps.push(scriptCommand(manager, "start"));
+
+  console.log("🦀 Rust + 🕸 Wasm = ❤");
+  console.log(`\nNext steps:\n${steps.map(step => `  ${step}`).join("\n")}`);
+  return 0;
 }
 
-const clone = spawn("git", ["clone", "https://github.com/rustwasm/create-wasm-app.git", folderName]);
+function add([spec]) {
+  if (!spec) {
+    throw new Error("`add` needs the package to add, e.g. `create-wasm-app add geometry-wasm`");
+  }
+  const entry = ["index.ts", "index.js"].find(file => fs.existsSync(file)) || "index.js";
+  const { name, binding } = addPackage(".", spec, { entry });
+  console.log(`Added ${name}, imported as \`${binding}\` in ${entry}.`);
+  return 0;
+}
 
-clone.on("close", code => {
-  if (code !== 0) {
-    console.error("cloning the template failed!")
-    process.exit(code);
-  } else {
-    console.log("🦀 Rust + 🕸 Wasm = ❤");
+function doctor([folder = "."]) {
+  const results = diagnose(folder);
+  for (const { status, message, hint } of results) {
+    console.log(`${STATUS_MARKS[status]} ${message}`);
+    if (hint) {
+      console.log(`    ${hint}`);
+    }
   }
-});
+  return results.some(result => result.status === "fail") ? 1 : 0;
+}
+
+function upgrade([folder = "."], { dryRun }) {
+  const plan = planUpgrade(folder);
+  if (plan.changes.length === 0 && plan.conflicts.length === 0) {
+    if (!dryRun) {
+      applyUpgrade(folder, plan);
+    }
+    console.log(`Already up to date with create-wasm-app ${plan.to}.`);
+    return 0;
+  }
+
+  process.stdout.write(plan.diff);
+  if (dryRun) {
+    return 0;
+  }
+  applyUpgrade(folder, plan);
+  console.log(`Upgraded from create-wasm-app ${plan.from} to ${plan.to}.`);
+  if (plan.changes.some(change => change.file === "package.json")) {
+    console.log(`package.json changed: run \`${detectPackageManager(folder)} install\`.`);
+  }
+
+  for (const { file, conflicts } of plan.conflicts) {
+    console.warn(file === "package.json"
+      ? `Kept your values in package.json where the template changed them too: ${conflicts.join(", ")}`
+      : `Both you and the template changed ${file}; resolve the conflicts marked in it.`);
+  }
+  if (plan.conflicts.length > 0) {
+    console.warn(`${MARKER} now records the new template, so upgrading again won't repeat them.`);
+    return 1;
+  }
+  return 0;
+}
+
+// The subcommands, and the options each of them accepts. Without one, the
+// arguments describe a project to create.
+const COMMANDS = {
+  add: { run: add, boolean: ["help"] },
+  doctor: { run: doctor, boolean: ["help"] },
+  upgrade: { run: upgrade, boolean: ["dry-run", "help"] },
+};
+
+const CREATE = {
+  run: create,
+  boolean: [
+    "force", "from-git", "git", "help", "install", "merge", "pwa",
+    "simd", "threads", "typescript", "with-crate", "worker", "yes",
+  ],
+  string: ["bundler", "config", "name", "pkg"],
+};
+
+// The options a `--config` file may set: the ones that describe the project.
+const CONFIG_OPTIONS = {
+  boolean: CREATE.boolean.filter(name => !["help", "yes"].includes(name)),
+  string: CREATE.string.filter(name => name !== "config"),
+};
+
+const argv = process.argv.slice(2);
+const command = COMMANDS[argv[0]];
+const { run, ...spec } = command || CREATE;
+
+let args;
+try {
+  args = parseArgs(command ? argv.slice(1) : argv, spec);
+} catch (e) {
+  console.error(`${e.message}\n\n${USAGE}`);
+  process.exit(1);
+}
+
+if (args.options.help) {
+  console.log(USAGE);
+  process.exit(0);
+}
+
+// Commands return the exit code, or a promise of it, and throw when they
+// can't go on.
+Promise.resolve()
+  .then(() => run(args.positionals, args.options))
+  .then(code => process.exit(code), e => {
+    console.error(e.message);
+    process.exit(1);
+  });
@@ -0,0 +1,123 @@
+// Scaffolds a project for every bundler with every combination of layers the
+// options allow, and checks that each generated file parses: JSON with
+// `JSON.parse`, JavaScript with `node --check`, as a CommonJS or an ES
+// module, and TypeScript with the `typescript` package's parser. A mistake in
+// a template's `{{#if}}` blocks otherwise only shows once someone builds the
+// project it generates.
+
+const { spawnSync } = require("child_process");
+const fs = require("fs");
+const path = require("path");
+const ts = require("typescript");
+const { resolveOptions } = require("../lib/options");
+const { BUNDLERS, listFiles, withTempDir } = require("../lib/template");
+
+const CLI = path.join(__dirname, "..", ".bin", "create-wasm-app.js");
+const FLAGS = ["withCrate", "simd", "threads", "worker", "pwa", "typescript"];
+const kebab = flag => flag.replace(/[A-Z]/g, c => `-${c.toLowerCase()}`);
+
+// Every combination of `FLAGS` with each bundler that `resolveOptions`
+// accepts.
+function combinations() {
+  const all = [];
+  for (const bundler of BUNDLERS) {
+    for (let bits = 0; bits < 2 ** FLAGS.length; bits++) {
+      const flags = { bundler };
+      FLAGS.forEach((flag, i) => {
+        if (bits & (1 << i)) {
+          flags[flag] = true;
+        }
+      });
+      try {
+        resolveOptions(["app"], flags);
+        all.push(flags);
+      } catch (e) {
+        // Options that don't fit together.
+      }
+    }
+  }
+  return all;
+}
+
+// The source is passed on stdin with its type, because newer versions of
+// Node guess the type of a `.js` file and let some syntax errors through.
+function checkJavaScript(file) {
+  const types = { ".mjs": ["module"], ".cjs": ["commonjs"] }[path.extname(file)] || ["commonjs", "module"];
+  let error;
+  for (const type of types) {
+    const result = spawnSync(process.execPath, [`--input-type=${type}`, "--check"], {
+      input: fs.readFileSync(file),
+      encoding: "utf8",
+    });
+    if (result.status === 0) {
+      return null;
+    }
+    error = result.stderr.trim();
+  }
+  return error;
+}
+
+function checkTypeScript(file) {
+  const { diagnostics } = ts.transpileModule(fs.readFileSync(file, "utf8"), {
+    fileName: file,
+    reportDiagnostics: true,
+  });
+  return diagnostics.length === 0
+    ? null
+    : diagnostics.map(d => ts.flattenDiagnosticMessageText(d.messageText, "\n")).join("\n");
+}
+
+// Why `file` doesn't parse, or `null` when it does.
+function check(file) {
+  const contents = fs.readFileSync(file, "utf8");
+  if (/\{\{\s*(#if|else|\/if)\b/.test(contents)) {
+    return "has a template tag left in it";
+  }
+  switch (path.extname(file)) {
+    case ".json":
+      try {
+        JSON.parse(contents);
+        return null;
+      } catch (e) {
+        return e.message;
+      }
+    case ".js":
+    case ".mjs":
+    case ".cjs":
+      return checkJavaScript(file);
+    case ".ts":
+      return checkTypeScript(file);
+    default:
+      return null;
+  }
+}
+
+let failures = 0;
+const all = combinations();
+withTempDir(tmp => {
+  all.forEach((flags, i) => {
+    const dir = path.join(tmp, `app-${i}`);
+    const args = [
+      `--bundler=${flags.bundler}`,
+      ...FLAGS.filter(flag => flags[flag]).map(flag => `--${kebab(flag)}`),
+    ];
+    const result = spawnSync(process.execPath, [CLI, dir, ...args, "--yes", "--no-install", "--no-git"], {
+      encoding: "utf8",
+    });
+    if (result.status !== 0) {
+      console.error(`✖ ${args.join(" ")}: scaffolding failed\n${result.stderr}`);
+      failures++;
+      return;
+    }
+    for (const file of listFiles(dir)) {
+      const error = check(path.join(dir, file));
+      if (error) {
+        console.error(`✖ ${args.join(" ")}: ${file}\n${error}`);
+        failures++;
+      }
+    }
+  });
+});
+
+console.log(`Checked ${all.length} projects: ${failures === 0 ? "all good" : `${failures} failures`}`);
+process.exit(failures === 0 ? 0 : 1);
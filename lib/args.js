@@ -0,0 +1,89 @@
+// A tiny argv parser. The CLI is run straight out of `npm init`, so it keeps
+// to Node's standard library instead of pulling in a dependency for this.
+
+const fs = require("fs");
+
+const camelCase = name => name.replace(/-([a-z])/g, (_, c) => c.toUpperCase());
+
+// Parses `argv` into positional arguments and options. `spec.boolean` and
+// `spec.string` list the accepted option names in their `--kebab-case` form;
+// the returned options use camelCase keys. Boolean options may be negated
+// with a `--no-` prefix.
+function parseArgs(argv, spec = {}) {
+  const booleans = new Set(spec.boolean || []);
+  const strings = new Set(spec.string || []);
+  const positionals = [];
+  const options = {};
+
+  for (let i = 0; i < argv.length; i++) {
+    const arg = argv[i];
+
+    if (arg === "--") {
+      positionals.push(...argv.slice(i + 1));
+      break;
+    }
+    if (!arg.startsWith("--") || arg === "-") {
+      positionals.push(arg);
+      continue;
+    }
+
+    const eq = arg.indexOf("=");
+    const name = eq === -1 ? arg.slice(2) : arg.slice(2, eq);
+    const inline = eq === -1 ? undefined : arg.slice(eq + 1);
+
+    if (booleans.has(name)) {
+      if (inline !== undefined) {
+        throw new Error(`option \`--${name}\` does not take a value`);
+      }
+      options[camelCase(name)] = true;
+    } else if (name.startsWith("no-") && booleans.has(name.slice(3))) {
+      options[camelCase(name.slice(3))] = false;
+    } else if (strings.has(name)) {
+      let value = inline;
+      if (value === undefined) {
+        if (i + 1 >= argv.length) {
+          throw new Error(`option \`--${name}\` requires a value`);
+        }
+        value = argv[++i];
+      }
+      options[camelCase(name)] = value;
+    } else {
+      throw new Error(`unknown option \`--${name}\``);
+    }
+  }
+
+  return { positionals, options };
+}
+
+// Reads options from the JSON object in `file`, for scripted use: its keys are
+// option names as they appear in `spec`, without the leading `--`, e.g.
+// `{ "bundler": "vite", "with-crate": true }`. Returns them like `parseArgs`
+// does.
+function readOptionsFile(file, spec = {}) {
+  let config;
+  try {
+    config = JSON.parse(fs.readFileSync(file, "utf8"));
+  } catch (e) {
+    throw new Error(`couldn't read options from \`${file}\`: ${e.message}`);
+  }
+  if (typeof config !== "object" || config === null || Array.isArray(config)) {
+    throw new Error(`\`${file}\` should hold a JSON object of options`);
+  }
+
+  const options = {};
+  for (const [name, value] of Object.entries(config)) {
+    const type = (spec.boolean || []).includes(name) ? "boolean"
+      : (spec.string || []).includes(name) ? "string"
+        : null;
+    if (!type) {
+      throw new Error(`unknown option \`${name}\` in \`${file}\``);
+    }
+    if (typeof value !== type) {
+      throw new Error(`option \`${name}\` in \`${file}\` should be a ${type}`);
+    }
+    options[camelCase(name)] = value;
+  }
+  return options;
+}
+
+module.exports = { parseArgs, readOptionsFile };
@@ -0,0 +1,173 @@
+const { spawnSync } = require("child_process");
+const fs = require("fs");
+const path = require("path");
+const { readJson } = require("./project");
+
+const WASM_TARGET = "wasm32-unknown-unknown";
+const RUSTUP = "https://rustup.rs";
+
+// Runs `command` and returns its trimmed output, or `null` when it isn't
+// installed or fails. npm is a batch file on Windows, which Node only runs
+// through a shell.
+function output(command, args, cwd) {
+  const result = spawnSync(command, args, {
+    cwd,
+    encoding: "utf8",
+    stdio: "pipe",
+    shell: process.platform === "win32",
+  });
+  if (result.error || result.status !== 0) {
+    return null;
+  }
+  return result.stdout.trim();
+}
+
+const ok = message => ({ status: "ok", message });
+const warn = (message, hint) => ({ status: "warn", message, hint });
+const fail = (message, hint) => ({ status: "fail", message, hint });
+
+// Compares two `major.minor.patch` versions, ignoring anything after them.
+function compareVersions(a, b) {
+  const parts = version => version.replace(/^v/, "").split(/[.+-]/).slice(0, 3).map(Number);
+  const [x, y] = [parts(a), parts(b)];
+  for (let i = 0; i < 3; i++) {
+    if ((x[i] || 0) !== (y[i] || 0)) {
+      return (x[i] || 0) - (y[i] || 0);
+    }
+  }
+  return 0;
+}
+
+// The project's Rust crate: `crate/` in projects made with `--with-crate`,
+// or the project itself when it is a crate.
+function crateDir(dir) {
+  return [path.join(dir, "crate"), dir].find(candidate =>
+    fs.existsSync(path.join(candidate, "Cargo.toml"))) || null;
+}
+
+// The version of wasm-bindgen the crate in `dir` is locked to, if any.
+function lockedWasmBindgen(dir) {
+  const lockfile = [dir, path.dirname(dir)]
+    .map(candidate => path.join(candidate, "Cargo.lock"))
+    .find(candidate => fs.existsSync(candidate));
+  if (!lockfile) {
+    return null;
+  }
+  const match = fs.readFileSync(lockfile, "utf8")
+    .match(/\[\[package\]\]\s+name = "wasm-bindgen"\s+version = "([^"]+)"/);
+  return match ? match[1] : null;
+}
+
+// Each check looks at one tool the project in `dir` needs, and reports
+// whether it is usable along with a hint for fixing it when it isn't.
+// Commands run in the crate's directory, if there is one, so that rustup
+// honours its `rust-toolchain.toml`.
+const CHECKS = [
+  function rustc({ rustDir }) {
+    const version = output("rustc", ["--version"], rustDir);
+    return version
+      ? ok(version)
+      : fail("rustc is not installed", `install Rust with rustup: ${RUSTUP}`);
+  },
+
+  function cargo({ rustDir }) {
+    const version = output("cargo", ["--version"], rustDir);
+    return version
+      ? ok(version)
+      : fail("cargo is not installed", `install Rust with rustup: ${RUSTUP}`);
+  },
+
+  function wasmTarget({ rustDir }) {
+    const targets = output("rustup", ["target", "list", "--installed"], rustDir);
+    if (targets === null) {
+      return warn(
+        `can't tell whether the ${WASM_TARGET} target is installed without rustup`,
+        `install Rust with rustup (${RUSTUP}), or make sure your toolchain has the ${WASM_TARGET} target`,
+      );
+    }
+    return targets.split("\n").includes(WASM_TARGET)
+      ? ok(`${WASM_TARGET} target`)
+      : fail(`the ${WASM_TARGET} target is not installed`, `rustup target add ${WASM_TARGET}`);
+  },
+
+  function wasmPack({ rustDir }) {
+    const version = output("wasm-pack", ["--version"], rustDir);
+    return version
+      ? ok(version)
+      : fail(
+        "wasm-pack is not installed",
+        "cargo install wasm-pack, or see https://rustwasm.github.io/wasm-pack/installer/",
+      );
+  },
+
+  function wasmOpt({ dir, rustDir }) {
+    // `build:prod` runs the copy the project's `binaryen` dependency installs.
+    const local = path.resolve(dir, "node_modules", ".bin", "wasm-opt");
+    const version = (fs.existsSync(local) && output(local, ["--version"], dir)) ||
+      output("wasm-opt", ["--version"], rustDir);
+    return version
+      ? ok(version)
+      : warn(
+        "wasm-opt is not installed; `build:prod` needs it to optimise the `.wasm` files",
+        "install the project's dependencies, or binaryen: https://github.com/WebAssembly/binaryen#releases",
+      );
+  },
+
+  function twiggy({ rustDir }) {
+    const version = output("twiggy", ["--version"], rustDir);
+    return version
+      ? ok(version)
+      : warn("twiggy is not installed; `size-report` needs it to break down the `.wasm` files", "cargo install twiggy");
+  },
+
+  function node({ dir }) {
+    const manifest = path.join(dir, "package.json");
+    const range = fs.existsSync(manifest) && (readJson(manifest).engines || {}).node;
+    const version = process.version;
+    if (!range) {
+      return ok(`node ${version}`);
+    }
+    const minimum = range.match(/^>=\s*(\d+(?:\.\d+){0,2})$/);
+    if (!minimum) {
+      return warn(`node ${version}; can't check it against \`engines.node\`: ${range}`);
+    }
+    return compareVersions(version, minimum[1]) >= 0
+      ? ok(`node ${version}`)
+      : fail(`node ${version} is too old, the project needs ${range}`, "install a newer Node.js: https://nodejs.org");
+  },
+
+  function npm({ dir }) {
+    const version = output("npm", ["--version"], dir);
+    return version
+      ? ok(`npm ${version}`)
+      : fail("npm is not installed", "it comes with Node.js: https://nodejs.org");
+  },
+
+  function wasmBindgen({ rustDir }) {
+    const locked = rustDir && lockedWasmBindgen(rustDir);
+    const installed = output("wasm-bindgen", ["--version"], rustDir);
+    const version = installed && installed.replace(/^wasm-bindgen\s+/, "").split(" ")[0];
+    if (!locked) {
+      return ok(installed || "no wasm-bindgen version to check: the project has no Cargo.lock yet");
+    }
+    if (!version) {
+      return ok(`wasm-bindgen ${locked}, wasm-pack downloads the matching CLI`);
+    }
+    return version === locked
+      ? ok(`wasm-bindgen ${locked}`)
+      : fail(
+        `the wasm-bindgen CLI is ${version}, but the crate uses wasm-bindgen ${locked}`,
+        `cargo install wasm-bindgen-cli --version ${locked}`,
+      );
+  },
+];
+
+// Checks the tools needed to build the project in `dir`, returning a result
+// for each: its `status` ("ok", "warn" or "fail"), a `message` and, unless it
+// passed, a `hint` for fixing it.
+function diagnose(dir) {
+  const context = { dir, rustDir: crateDir(dir) || dir };
+  return CHECKS.map(check => check(context));
+}
+
+module.exports = { diagnose };
@@ -0,0 +1,50 @@
+const fs = require("fs");
+const path = require("path");
+
+// Matches the declarations wasm-bindgen writes for exported functions, e.g.
+// `export function add(a: number, b: number): number;`
+const FUNCTION = /^export function (\w+)\(([^)]*)\)(?:\s*:\s*([^;]+))?;/gm;
+const CLASS = /^export class (\w+)/gm;
+
+// Lists the functions and classes declared in a wasm-bindgen `.d.ts` file.
+function parseDeclarations(source) {
+  const functions = [];
+  for (const [, name, params, returns] of source.matchAll(FUNCTION)) {
+    functions.push({ name, params: params.trim(), returns: (returns || "void").trim() });
+  }
+  const classes = [];
+  for (const [, name] of source.matchAll(CLASS)) {
+    classes.push({ name });
+  }
+  return { functions, classes };
+}
+
+// Finds the typings of the npm package in `pkgDir`, following the same
+// fields wasm-pack fills in, or returns `null` when it has none.
+function typingsFile(pkgDir) {
+  const manifest = path.join(pkgDir, "package.json");
+  if (!fs.existsSync(manifest)) {
+    return null;
+  }
+  const pkg = JSON.parse(fs.readFileSync(manifest, "utf8"));
+  const candidates = [pkg.types, pkg.typings];
+  const main = pkg.module || pkg.main;
+  if (main) {
+    candidates.push(main.replace(/\.m?js$/, ".d.ts"));
+  }
+  for (const candidate of candidates) {
+    if (candidate && fs.existsSync(path.join(pkgDir, candidate))) {
+      return path.join(pkgDir, candidate);
+    }
+  }
+  return null;
+}
+
+// Reads the exports of the package in `pkgDir` from its typings, or returns
+// `null` when they can't be found.
+function packageExports(pkgDir) {
+  const file = typingsFile(pkgDir);
+  return file ? parseDeclarations(fs.readFileSync(file, "utf8")) : null;
+}
+
+module.exports = { packageExports, parseDeclarations, typingsFile };
@@ -0,0 +1,35 @@
+const { spawnSync } = require("child_process");
+const path = require("path");
+const { removeDir } = require("./template");
+
+function git(cwd, args) {
+  return spawnSync("git", args, { cwd, stdio: "pipe", encoding: "utf8" });
+}
+
+// Turns `dir` into a new git repository holding a single commit with the
+// generated files. Returns a reason when no repository was created, so the
+// caller can tell the user; failing to set up git never fails scaffolding.
+function initRepository(dir) {
+  const inside = git(dir, ["rev-parse", "--is-inside-work-tree"]);
+  if (inside.error) {
+    return "git is not installed";
+  }
+  if (inside.status === 0 && inside.stdout.trim() === "true") {
+    return "the project is already inside a git repository";
+  }
+
+  for (const args of [
+    ["init"],
+    ["add", "-A"],
+    ["commit", "-m", "Initial commit from create-wasm-app"],
+  ]) {
+    const step = git(dir, args);
+    if (step.status !== 0) {
+      removeDir(path.join(dir, ".git"));
+      return `\`git ${args[0]}\` failed: ${step.stderr.trim().split("\n")[0]}`;
+    }
+  }
+  return null;
+}
+
+module.exports = { initRepository };
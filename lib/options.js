@@ -0,0 +1,120 @@
+const { crateNameFor, nameFromFolder, validateName } = require("./project");
+const { BUNDLERS } = require("./template");
+const { DEFAULT_PACKAGE, resolvePackage } = require("./wasm-package");
+
+// Bundlers whose dev server can send the headers that make a page cross-origin
+// isolated, and that understand the `new Worker(new URL(...))` wasm-pack's
+// `web` target output uses to start threads.
+const THREADS_BUNDLERS = ["webpack", "vite"];
+
+// Bundlers that bundle a module worker started with
+// `new Worker(new URL(...), { type: "module" })` as an entry point of its own.
+const WORKER_BUNDLERS = ["webpack", "vite"];
+
+// Checks the scaffolding options given on the command line and fills in
+// defaults, returning everything needed to generate the project. Throws when
+// the options are invalid or don't fit together.
+function resolveOptions(positionals, flags) {
+  const folder = positionals[0] || ".";
+  const bundler = flags.bundler || BUNDLERS[0];
+  if (!BUNDLERS.includes(bundler)) {
+    throw new Error(`unknown bundler \`${bundler}\`, expected one of: ${BUNDLERS.join(", ")}`);
+  }
+  if (flags.force && flags.merge) {
+    throw new Error("`--force` and `--merge` can't be used together");
+  }
+  if (flags.pkg && flags.withCrate) {
+    throw new Error("`--pkg` and `--with-crate` can't be used together");
+  }
+  if (flags.simd && !flags.withCrate) {
+    throw new Error("`--simd` builds the project's own crate, so it needs `--with-crate`");
+  }
+  if (flags.threads) {
+    if (!flags.withCrate) {
+      throw new Error("`--threads` builds the project's own crate, so it needs `--with-crate`");
+    }
+    if (flags.simd) {
+      throw new Error("`--threads` and `--simd` can't be used together");
+    }
+    if (!THREADS_BUNDLERS.includes(bundler)) {
+      throw new Error(`\`--threads\` needs one of these bundlers: ${THREADS_BUNDLERS.join(", ")}`);
+    }
+  }
+  if (flags.worker) {
+    if (flags.simd || flags.threads) {
+      throw new Error(`\`--worker\` and \`--${flags.simd ? "simd" : "threads"}\` can't be used together`);
+    }
+    if (!WORKER_BUNDLERS.includes(bundler)) {
+      throw new Error(`\`--worker\` needs one of these bundlers: ${WORKER_BUNDLERS.join(", ")}`);
+    }
+  }
+
+  return {
+    folder,
+    name: flags.name ? validateName(flags.name) : nameFromFolder(folder),
+    bundler,
+    pkg: flags.pkg || null,
+    withCrate: Boolean(flags.withCrate),
+    simd: Boolean(flags.simd),
+    threads: Boolean(flags.threads),
+    worker: Boolean(flags.worker),
+    pwa: Boolean(flags.pwa),
+    typescript: Boolean(flags.typescript),
+    fromGit: Boolean(flags.fromGit),
+    // What to do with files of the project the folder already has.
+    existing: flags.force ? "overwrite" : flags.merge ? "keep" : null,
+    git: flags.git !== false,
+    install: flags.install !== false,
+  };
+}
+
+// The template layers a project generated with `options` is made of.
+function layersFor(options) {
+  const layers = ["base", options.bundler];
+  if (options.withCrate) {
+    layers.push("crate");
+  }
+  if (options.simd) {
+    layers.push("simd");
+  }
+  if (options.threads) {
+    layers.push("threads");
+  }
+  if (options.worker) {
+    layers.push("worker");
+  }
+  if (options.pwa) {
+    layers.push("pwa");
+  }
+  if (options.typescript) {
+    layers.push("typescript");
+  }
+  return layers;
+}
+
+// The name of the wasm package the app is built around.
+function wasmPackageFor(options) {
+  if (options.pkg) {
+    return resolvePackage(options.pkg, options.folder).name;
+  }
+  return options.withCrate ? crateNameFor(options.name) : DEFAULT_PACKAGE;
+}
+
+// The variables the template is rendered with.
+function varsFor(options) {
+  return {
+    name: options.name,
+    bundler: options.bundler,
+    crate: options.withCrate,
+    crateName: crateNameFor(options.name),
+    wasmPackage: wasmPackageFor(options),
+    simd: options.simd,
+    threads: options.threads,
+    worker: options.worker,
+    pwa: options.pwa,
+    typescript: options.typescript,
+    scriptExt: options.typescript ? "ts" : "js",
+  };
+}
+
+module.exports = { THREADS_BUNDLERS, layersFor, resolveOptions, varsFor };
@@ -0,0 +1,112 @@
+const { spawnSync } = require("child_process");
+const fs = require("fs");
+const path = require("path");
+
+// The package managers projects can be set up with; the first one is the
+// default. Each has the arguments for installing a project's dependencies,
+// and for adding and removing a dev dependency.
+const PACKAGE_MANAGERS = {
+  npm: { install: ["install"], add: ["install", "--save-dev"], remove: ["uninstall"] },
+  yarn: { install: ["install"], add: ["add", "--dev"], remove: ["remove"] },
+  pnpm: { install: ["install"], add: ["add", "--save-dev"], remove: ["remove"] },
+};
+
+const LOCKFILES = {
+  "pnpm-lock.yaml": "pnpm",
+  "yarn.lock": "yarn",
+  "package-lock.json": "npm",
+};
+
+// Picks the package manager for the project in `dir`: the one whose lockfile
+// it has, or else the one running this command, which package managers
+// announce in `npm_config_user_agent`, e.g. `pnpm/8.15.1 npm/? node/v20.11.0`.
+function detectPackageManager(dir, env = process.env) {
+  for (const [lockfile, manager] of Object.entries(LOCKFILES)) {
+    if (dir && fs.existsSync(path.join(dir, lockfile))) {
+      return manager;
+    }
+  }
+  const agent = (env.npm_config_user_agent || "").split("/")[0];
+  return agent in PACKAGE_MANAGERS ? agent : Object.keys(PACKAGE_MANAGERS)[0];
+}
+
+// The major version of yarn run in `dir`, which corepack picks from its
+// `packageManager` field, or `null` when yarn can't be run.
+function yarnVersion(dir) {
+  const result = spawnSync("yarn", ["--version"], {
+    cwd: dir,
+    encoding: "utf8",
+    stdio: "pipe",
+    shell: process.platform === "win32",
+  });
+  return result.status === 0 ? parseInt(result.stdout, 10) : null;
+}
+
+// Yarn 2 and later install packages with Plug'n'Play by default, leaving no
+// node_modules for the bundlers and the project's scripts to find them in.
+// When `manager` is such a yarn, sets up the project in `dir` to be installed
+// into node_modules instead.
+function configurePackageManager(manager, dir) {
+  if (manager !== "yarn" || !(yarnVersion(dir) >= 2)) {
+    return;
+  }
+  const yarnrc = path.join(dir, ".yarnrc.yml");
+  const settings = fs.existsSync(yarnrc) ? fs.readFileSync(yarnrc, "utf8") : "";
+  if (!/^nodeLinker:/m.test(settings)) {
+    const separator = settings && !settings.endsWith("\n") ? "\n" : "";
+    fs.writeFileSync(yarnrc, `${settings}${separator}nodeLinker: node-modules\n`);
+  }
+  const gitignore = path.join(dir, ".gitignore");
+  if (fs.existsSync(gitignore) && !/^\.yarn\/?$/m.test(fs.readFileSync(gitignore, "utf8"))) {
+    fs.appendFileSync(gitignore, ".yarn\n");
+  }
+}
+
+// Whether the project in `dir` has its packages installed with Yarn's
+// Plug'n'Play, rather than into node_modules.
+const usesPlugNPlay = dir => fs.existsSync(path.join(dir, ".pnp.cjs"));
+
+// How to run the package.json script `script` with `manager`.
+function scriptCommand(manager, script) {
+  return manager === "npm" && script !== "start" ? `npm run ${script}` : `${manager} ${script}`;
+}
+
+function spawn(manager, args, dir) {
+  // npm and pnpm are batch files on Windows, which Node only runs through a
+  // shell.
+  const result = spawnSync(manager, args, {
+    cwd: dir,
+    stdio: "inherit",
+    shell: process.platform === "win32",
+  });
+  if (result.error) {
+    throw new Error(`couldn't run ${manager}: ${result.error.message}`);
+  }
+  return result.status === 0;
+}
+
+// Runs `manager` with the arguments for `action` followed by `args` in
+// `dir`, showing its output. Throws when it fails.
+function runPackageManager(manager, action, dir, args = []) {
+  const command = [...PACKAGE_MANAGERS[manager][action], ...args];
+  if (!spawn(manager, command, dir)) {
+    throw new Error(`\`${manager} ${command.join(" ")}\` failed`);
+  }
+}
+
+// Runs the package.json script `script` of the project in `dir`.
+function runScript(manager, dir, script) {
+  if (!spawn(manager, ["run", script], dir)) {
+    throw new Error(`\`${scriptCommand(manager, script)}\` failed`);
+  }
+}
+
+module.exports = {
+  PACKAGE_MANAGERS,
+  configurePackageManager,
+  detectPackageManager,
+  runPackageManager,
+  runScript,
+  scriptCommand,
+  usesPlugNPlay,
+};
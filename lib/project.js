@@ -0,0 +1,98 @@
+const { spawnSync } = require("child_process");
+const fs = require("fs");
+const path = require("path");
+
+// The rules npm applies to new package names, minus the length limit.
+const VALID_NAME = /^(@[a-z0-9-~][a-z0-9-._~]*\/)?[a-z0-9-~][a-z0-9-._~]*$/;
+
+// Template fields that describe create-wasm-app itself rather than the
+// project generated from it.
+const TEMPLATE_FIELDS = ["bin", "files", "repository", "bugs", "homepage"];
+
+function validateName(name) {
+  if (!VALID_NAME.test(name)) {
+    throw new Error(`\`${name}\` is not a valid npm package name`);
+  }
+  return name;
+}
+
+// Derives a package name from the folder the project is created in.
+function nameFromFolder(folderName) {
+  const name = path.basename(path.resolve(folderName))
+    .toLowerCase()
+    .replace(/[^a-z0-9-._~]+/g, "-")
+    .replace(/^[-._]+|-+$/g, "");
+  return name || "wasm-app";
+}
+
+// Picks a name for the project's own Rust crate. It must differ from the
+// package name, since the app depends on the npm package wasm-pack builds
+// from the crate.
+function crateNameFor(name) {
+  const base = name.replace(/^@[^/]+\//, "").replace(/[^a-z0-9_-]+/g, "-");
+  return /wasm$/.test(base) ? `${base}-rs` : `${base}-wasm`;
+}
+
+function gitConfig(key) {
+  const result = spawnSync("git", ["config", "--get", key], { encoding: "utf8" });
+  return result.status === 0 ? result.stdout.trim() : "";
+}
+
+function gitAuthor() {
+  const name = gitConfig("user.name");
+  const email = gitConfig("user.email");
+  return email ? `${name} <${email}>`.trim() : name;
+}
+
+// Returns a copy of `object` with its keys in alphabetical order, the way npm
+// writes dependency lists.
+function sortKeys(object) {
+  return Object.fromEntries(Object.keys(object).sort().map(key => [key, object[key]]));
+}
+
+function readJson(file) {
+  return JSON.parse(fs.readFileSync(file, "utf8"));
+}
+
+function writeJson(file, value) {
+  fs.writeFileSync(file, JSON.stringify(value, null, 2) + "\n");
+}
+
+// Rewrites the files copied from the template so they describe a project
+// called `name` instead of the template itself. The package's `author` is
+// the git user running this, unless given.
+function personalize(dir, { name, author = gitAuthor() }) {
+  const manifest = path.join(dir, "package.json");
+  const pkg = readJson(manifest);
+  pkg.name = name;
+  pkg.version = "0.1.0";
+  pkg.author = author;
+  for (const field of TEMPLATE_FIELDS) {
+    delete pkg[field];
+  }
+  writeJson(manifest, pkg);
+
+  const lockfile = path.join(dir, "package-lock.json");
+  if (fs.existsSync(lockfile)) {
+    const lock = readJson(lockfile);
+    lock.name = name;
+    lock.version = pkg.version;
+    writeJson(lockfile, lock);
+  }
+
+  const page = path.join(dir, "index.html");
+  if (fs.existsSync(page)) {
+    const html = fs.readFileSync(page, "utf8");
+    fs.writeFileSync(page, html.replace(/<title>.*<\/title>/, `<title>${name}</title>`));
+  }
+}
+
+module.exports = {
+  crateNameFor,
+  nameFromFolder,
+  personalize,
+  readJson,
+  sortKeys,
+  validateName,
+  writeJson,
+};
@@ -0,0 +1,118 @@
+const readline = require("readline");
+const { THREADS_BUNDLERS } = require("./options");
+const { nameFromFolder, validateName } = require("./project");
+const { BUNDLERS } = require("./template");
+
+// Reads answers line by line from the terminal. Lines are queued rather than
+// read with `rl.question`, which drops those typed ahead or piped in before
+// their question is asked.
+function createPrompter() {
+  const rl = readline.createInterface({ input: process.stdin, output: process.stdout });
+  const lines = [];
+  let waiting = null;
+  let closed = false;
+  rl.on("line", line => {
+    if (waiting) {
+      waiting.resolve(line);
+      waiting = null;
+    } else {
+      lines.push(line);
+    }
+  });
+  rl.on("close", () => {
+    closed = true;
+    if (waiting) {
+      waiting.reject(new Error("Cancelled, nothing was created."));
+    }
+  });
+  rl.on("SIGINT", () => rl.close());
+
+  const nextLine = () => {
+    if (lines.length > 0) {
+      return Promise.resolve(lines.shift());
+    }
+    if (closed) {
+      return Promise.reject(new Error("Cancelled, nothing was created."));
+    }
+    return new Promise((resolve, reject) => {
+      waiting = { resolve, reject };
+    });
+  };
+
+  return {
+    // Asks `question` until `parse` accepts the answer, and resolves to what
+    // it returns. `parse` throws to explain why an answer is rejected.
+    async ask(question, parse) {
+      for (;;) {
+        rl.setPrompt(question);
+        rl.prompt();
+        const answer = await nextLine();
+        try {
+          return parse(answer.trim());
+        } catch (e) {
+          console.log(`  ${e.message}`);
+        }
+      }
+    },
+    close: () => rl.close(),
+  };
+}
+
+const yesOrNo = answer => {
+  if (/^y(es)?$/i.test(answer)) {
+    return true;
+  }
+  if (!answer || /^no?$/i.test(answer)) {
+    return false;
+  }
+  throw new Error("answer y or n");
+};
+
+function pickBundler(answer) {
+  if (!answer) {
+    return BUNDLERS[0];
+  }
+  if (!BUNDLERS.includes(answer)) {
+    throw new Error(`pick one of: ${BUNDLERS.join(", ")}`);
+  }
+  return answer;
+}
+
+// Asks for the scaffolding options that `flags`, the options given on the
+// command line, leaves out, and returns them with the answers added.
+// Questions that earlier answers make moot are skipped.
+async function promptOptions(folder, flags) {
+  const prompter = createPrompter();
+  const answers = { ...flags };
+  try {
+    if (answers.name === undefined) {
+      const fallback = nameFromFolder(folder);
+      answers.name = await prompter.ask(`Package name (${fallback}): `,
+        answer => (answer ? validateName(answer) : fallback));
+    }
+    if (answers.bundler === undefined) {
+      answers.bundler = await prompter.ask(
+        `Bundler: ${BUNDLERS.join(", ")} (${BUNDLERS[0]}): `, pickBundler);
+    }
+    if (answers.typescript === undefined) {
+      answers.typescript = await prompter.ask("Write the app in TypeScript? (y/N) ", yesOrNo);
+    }
+    if (answers.withCrate === undefined && !answers.pkg) {
+      answers.withCrate = await prompter.ask(
+        "Add a Rust crate to write the wasm side in? (y/N) ", yesOrNo);
+    }
+    if (answers.threads === undefined && answers.withCrate && !answers.simd && !answers.worker &&
+        THREADS_BUNDLERS.includes(answers.bundler)) {
+      answers.threads = await prompter.ask("Run the crate on several threads? (y/N) ", yesOrNo);
+    }
+    if (answers.pwa === undefined) {
+      answers.pwa = await prompter.ask(
+        "Make the app an installable PWA that works offline? (y/N) ", yesOrNo);
+    }
+  } finally {
+    prompter.close();
+  }
+  return answers;
+}
+
+module.exports = { promptOptions };
@@ -0,0 +1,78 @@
+// A deliberately small template language for the files in `template/`:
+//
+// - `{{name}}` is replaced with `vars.name`.
+// - `{{#if cond}}`, `{{else}}` and `{{/if}}` keep or drop what they enclose.
+//   `cond` is a variable name, optionally negated with `!`, or a comparison
+//   like `bundler == "vite"` or `bundler != "webpack"`.
+//
+// Block tags that sit on a line of their own take the whole line with them,
+// so they don't leave blank lines behind in the output. Unknown variables are
+// an error rather than being left in the generated project.
+
+const TAG = /\{\{\s*(#if\s+[^}]+?|else|\/if|\w+)\s*\}\}/g;
+const STANDALONE_BLOCK = /^[ \t]*(\{\{\s*(?:#if\s+[^}]+?|else|\/if)\s*\}\})[ \t]*\r?\n/gm;
+
+function lookup(vars, name) {
+  if (!(name in vars)) {
+    throw new Error(`template variable \`${name}\` is not defined`);
+  }
+  return vars[name];
+}
+
+function evaluate(condition, vars) {
+  const comparison = condition.match(/^(\w+)\s*(==|!=)\s*"([^"]*)"$/);
+  if (comparison) {
+    const [, name, op, value] = comparison;
+    return (lookup(vars, name) === value) === (op === "==");
+  }
+  const flag = condition.match(/^(!?)(\w+)$/);
+  if (flag) {
+    return Boolean(lookup(vars, flag[2])) !== (flag[1] === "!");
+  }
+  throw new Error(`can't understand template condition \`${condition}\``);
+}
+
+function render(source, vars) {
+  const template = source.replace(STANDALONE_BLOCK, "$1");
+  let output = "";
+  let last = 0;
+  // One entry per open `{{#if}}`: whether its current branch is kept, and
+  // whether one of its branches already was.
+  const stack = [];
+  const emitting = () => stack.every(block => block.keep);
+
+  for (const match of template.matchAll(TAG)) {
+    const text = template.slice(last, match.index);
+    last = match.index + match[0].length;
+    if (emitting()) {
+      output += text;
+    }
+
+    const tag = match[1];
+    if (tag.startsWith("#if")) {
+      const keep = emitting() ? evaluate(tag.slice(3).trim(), vars) : false;
+      stack.push({ keep, taken: keep });
+    } else if (tag === "else") {
+      const block = stack[stack.length - 1];
+      if (!block) {
+        throw new Error("`{{else}}` outside of an `{{#if}}` block");
+      }
+      const outer = stack.slice(0, -1).every(b => b.keep);
+      block.keep = outer && !block.taken;
+      block.taken = true;
+    } else if (tag === "/if") {
+      if (!stack.pop()) {
+        throw new Error("`{{/if}}` without a matching `{{#if}}`");
+      }
+    } else if (emitting()) {
+      output += lookup(vars, tag);
+    }
+  }
+
+  if (stack.length > 0) {
+    throw new Error("unclosed `{{#if}}` block");
+  }
+  return output + template.slice(last);
+}
+
+module.exports = { render };
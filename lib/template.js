@@ -0,0 +1,223 @@
+const { spawnSync } = require("child_process");
+const fs = require("fs");
+const os = require("os");
+const path = require("path");
+const { readJson, sortKeys, writeJson } = require("./project");
+const { render } = require("./render");
+
+const PACKAGE_DIR = path.join(__dirname, "..");
+const TEMPLATE_REPO = "https://github.com/rustwasm/create-wasm-app.git";
+
+// Projects are licensed the same way as this package, so the license files
+// are copied from the package root instead of being duplicated in `template/`.
+const LICENSES = ["LICENSE-APACHE", "LICENSE-MIT"];
+
+// npm refuses to publish files named `.gitignore`, so the template keeps them
+// under another name and they are renamed on the way out.
+const RENAMES = { gitignore: ".gitignore" };
+
+// Checkouts of the template from before it moved into `template/` have the
+// project files at the repository root, next to the CLI itself. They only
+// know about webpack, and have no other layers.
+const LEGACY_EXCLUDES = [".git", ".bin"];
+const LEGACY_LAYERS = ["base", "webpack"];
+
+// Modules of this package that layers ship with generated projects too, so
+// that there is a single copy of them: for each layer, where each module goes
+// in the project and where it comes from.
+const SHARED_MODULES = {
+  worker: { "scripts/dts.js": "lib/dts.js" },
+};
+
+// The bundlers there is a template layer for; the first one is the default.
+const BUNDLERS = ["webpack", "vite", "rollup", "esbuild"];
+
+function removeDir(dir) {
+  if (fs.rmSync) {
+    fs.rmSync(dir, { recursive: true, force: true });
+  } else {
+    fs.rmdirSync(dir, { recursive: true });
+  }
+}
+
+// Calls `fn` with a new temporary directory, which is removed afterwards.
+function withTempDir(fn) {
+  const dir = fs.mkdtempSync(path.join(os.tmpdir(), "create-wasm-app-"));
+  try {
+    return fn(dir);
+  } finally {
+    removeDir(dir);
+  }
+}
+
+// Lists the files under `dir`, with `/` separated paths relative to it.
+function listFiles(dir, prefix = "") {
+  return fs.readdirSync(path.join(dir, prefix), { withFileTypes: true }).flatMap(entry => {
+    const file = prefix ? `${prefix}/${entry.name}` : entry.name;
+    return entry.isDirectory() ? listFiles(dir, file) : [file];
+  });
+}
+
+function copyDir(src, dest, exclude = []) {
+  fs.mkdirSync(dest, { recursive: true });
+  for (const entry of fs.readdirSync(src, { withFileTypes: true })) {
+    if (exclude.includes(entry.name)) {
+      continue;
+    }
+    const from = path.join(src, entry.name);
+    const to = path.join(dest, RENAMES[entry.name] || entry.name);
+    if (entry.isDirectory()) {
+      copyDir(from, to);
+    } else {
+      fs.copyFileSync(from, to);
+    }
+  }
+}
+
+// Merges `overlay` into `base` key by key. A `null` in the overlay removes
+// the key, which lets a layer drop dependencies the base template adds.
+function mergeJson(base, overlay) {
+  const merged = { ...base };
+  for (const [key, value] of Object.entries(overlay)) {
+    if (value === null) {
+      delete merged[key];
+    } else if (typeof value === "object" && !Array.isArray(value) &&
+               typeof merged[key] === "object" && !Array.isArray(merged[key])) {
+      merged[key] = mergeJson(merged[key], value);
+    } else {
+      merged[key] = value;
+    }
+  }
+  return merged;
+}
+
+// Writes a rendered template file to `to`. Layers on top of the base one
+// extend `package.json` and `.gitignore` rather than replacing them.
+function writeLayerFile(to, contents) {
+  const name = path.basename(to);
+  if (name === "package.json" && fs.existsSync(to)) {
+    const pkg = mergeJson(readJson(to), JSON.parse(contents));
+    for (const field of ["dependencies", "devDependencies"]) {
+      if (pkg[field]) {
+        pkg[field] = sortKeys(pkg[field]);
+      }
+    }
+    writeJson(to, pkg);
+  } else if (name === ".gitignore" && fs.existsSync(to)) {
+    const lines = fs.readFileSync(to, "utf8").split("\n").filter(Boolean);
+    const added = contents.split("\n").filter(line => line && !lines.includes(line));
+    fs.writeFileSync(to, [...lines, ...added].join("\n") + "\n");
+  } else {
+    fs.writeFileSync(to, contents);
+  }
+}
+
+// File names in a layer are templates too, e.g. `index.{{scriptExt}}`.
+function copyLayer(src, dest, vars) {
+  fs.mkdirSync(dest, { recursive: true });
+  for (const entry of fs.readdirSync(src, { withFileTypes: true })) {
+    const from = path.join(src, entry.name);
+    const to = path.join(dest, RENAMES[entry.name] || render(entry.name, vars));
+    if (entry.isDirectory()) {
+      copyLayer(from, to, vars);
+    } else {
+      writeLayerFile(to, render(fs.readFileSync(from, "utf8"), vars));
+    }
+  }
+}
+
+// Copies the template found in the checkout or package at `root` into `dest`.
+// The template is made of layers: `base` holds the app every project starts
+// from, one of the `BUNDLERS` layers sets up how it is built and served, and
+// the others add optional pieces on top of it.
+function copyTemplate(root, dest, { layers = ["base"], vars = {} } = {}) {
+  const templateDir = path.join(root, "template");
+  if (!fs.existsSync(templateDir)) {
+    const extra = layers.filter(layer => !LEGACY_LAYERS.includes(layer));
+    if (extra.length > 0) {
+      throw new Error(`this version of the template has no \`${extra[0]}\` layer`);
+    }
+    copyDir(root, dest, LEGACY_EXCLUDES);
+    return;
+  }
+
+  for (const layer of layers) {
+    const layerDir = path.join(templateDir, layer);
+    if (!fs.existsSync(layerDir)) {
+      throw new Error(`this version of the template has no \`${layer}\` layer`);
+    }
+    copyLayer(layerDir, dest, vars);
+    for (const [to, from] of Object.entries(SHARED_MODULES[layer] || {})) {
+      fs.copyFileSync(path.join(root, from), path.join(dest, to));
+    }
+  }
+  for (const license of LICENSES) {
+    fs.copyFileSync(path.join(root, license), path.join(dest, license));
+  }
+}
+
+// Clones the template repository into a temporary directory and returns its
+// path. The caller is responsible for removing it.
+function cloneTemplate() {
+  const dir = fs.mkdtempSync(path.join(os.tmpdir(), "create-wasm-app-"));
+  const clone = spawnSync("git", ["clone", "--depth", "1", TEMPLATE_REPO, dir], {
+    stdio: "inherit",
+  });
+  if (clone.error || clone.status !== 0) {
+    removeDir(dir);
+    throw new Error(clone.error && clone.error.code === "ENOENT"
+      ? "`--from-git` needs git to be installed"
+      : "cloning the template failed!");
+  }
+  return dir;
+}
+
+// Writes a fresh copy of the template's `layers` into `dest`, filled in from
+// `vars`. The template shipped with this package is used unless `fromGit`
+// asks for the latest one on GitHub.
+function scaffold(dest, { fromGit = false, layers, vars } = {}) {
+  if (!fromGit) {
+    copyTemplate(PACKAGE_DIR, dest, { layers, vars });
+    return;
+  }
+
+  const checkout = cloneTemplate();
+  try {
+    copyTemplate(checkout, dest, { layers, vars });
+  } finally {
+    removeDir(checkout);
+  }
+}
+
+// Copies the project generated in `staging` into `dest`, which may already
+// hold files. The ones the project would replace are an error listing them,
+// unless `existing` says what to do with them: "overwrite" or "keep".
+function installProject(staging, dest, { existing = null } = {}) {
+  const files = listFiles(staging);
+  const conflicts = files.filter(file => fs.existsSync(path.join(dest, file)));
+  if (conflicts.length > 0 && !existing) {
+    throw new Error([
+      `\`${dest}\` already has files the new project would replace:`,
+      ...conflicts.map(file => `  ${file}`),
+      "Pass `--force` to overwrite them, or `--merge` to only add the missing files.",
+    ].join("\n"));
+  }
+
+  for (const file of files) {
+    const to = path.join(dest, file);
+    if (existing === "keep" && conflicts.includes(file)) {
+      continue;
+    }
+    fs.mkdirSync(path.dirname(to), { recursive: true });
+    fs.copyFileSync(path.join(staging, file), to);
+  }
+}
+
+module.exports = {
+  BUNDLERS,
+  installProject,
+  listFiles,
+  removeDir,
+  scaffold,
+  withTempDir,
+};
@@ -0,0 +1,275 @@
+const { spawnSync } = require("child_process");
+const fs = require("fs");
+const path = require("path");
+const { layersFor, resolveOptions, varsFor } = require("./options");
+const { personalize, readJson, sortKeys, writeJson } = require("./project");
+const { listFiles, scaffold, withTempDir } = require("./template");
+const { replaceDefaultPackage } = require("./wasm-package");
+
+const PACKAGE_DIR = path.join(__dirname, "..");
+const VERSION = readJson(path.join(PACKAGE_DIR, "package.json")).version;
+
+// Records how a project was generated, along with the generated copy of the
+// files `upgrade` merges changes into: the common ancestor of the project's
+// copy and the next version of the template.
+const MARKER = ".create-wasm-app.json";
+
+// The options that shape the generated files, and so are recorded.
+const RECORDED_OPTIONS = [
+  "name", "bundler", "withCrate", "simd", "threads", "worker", "pwa", "typescript",
+];
+
+const CONFIG_FILES = {
+  webpack: "webpack.config.js",
+  vite: "vite.config.js",
+  rollup: "rollup.config.mjs",
+  esbuild: "esbuild.config.mjs",
+};
+
+// Projects from before the marker existed were all made from the same
+// webpack 4 template, kept in `legacy-template/`.
+const LEGACY_VERSION = "0.1.0 (webpack 4)";
+const LEGACY_DIR = path.join(PACKAGE_DIR, "legacy-template");
+
+// Files that belong to the app's author, which `upgrade` never adds back
+// when they are missing.
+const USER_FILES = [/^index\.(js|ts)$/, /^crate\//, /^LICENSE-/];
+
+// The files the template keeps up to date in a project made with `options`:
+// `upgrade` merges the template's changes to them with the project's own.
+function managedFiles(options) {
+  const scriptExt = options.typescript ? "ts" : "js";
+  return ["package.json", "index.html", `bootstrap.${scriptExt}`, CONFIG_FILES[options.bundler]];
+}
+
+function git(cwd, args) {
+  const result = spawnSync("git", args, { cwd, encoding: "utf8", stdio: "pipe" });
+  if (result.error) {
+    throw new Error("`upgrade` needs git to be installed to merge changes");
+  }
+  return result;
+}
+
+function readFile(file) {
+  return fs.existsSync(file) ? fs.readFileSync(file, "utf8") : null;
+}
+
+// The marker of a project generated in `dir` from `options`, depending on
+// the wasm package `pkg` (`{ name, version }`) when it isn't the template's
+// default one.
+function markerFor(dir, options, pkg) {
+  const files = {};
+  for (const file of managedFiles(options)) {
+    const contents = readFile(path.join(dir, file));
+    if (contents !== null) {
+      files[file] = contents;
+    }
+  }
+  const recorded = Object.fromEntries(RECORDED_OPTIONS.map(key => [key, options[key]]));
+  return { version: VERSION, options: recorded, package: pkg, files };
+}
+
+function writeMarker(dir, options, pkg = null) {
+  writeJson(path.join(dir, MARKER), markerFor(dir, options, pkg));
+}
+
+// Records in the marker of the project in `dir` that it depends on `pkg`
+// (`{ name, version }`), once installing it has settled the version.
+function recordPackage(dir, pkg) {
+  const file = path.join(dir, MARKER);
+  const marker = readJson(file);
+  marker.package = pkg;
+  if (marker.files["package.json"]) {
+    const manifest = JSON.parse(marker.files["package.json"]);
+    manifest.devDependencies[pkg.name] = pkg.version;
+    marker.files["package.json"] = JSON.stringify(manifest, null, 2) + "\n";
+  }
+  writeJson(file, marker);
+}
+
+// Reads the marker of the project in `dir`, or makes one up for projects
+// generated from the legacy template, recognised by their webpack 4 setup.
+function readMarker(dir) {
+  const marker = path.join(dir, MARKER);
+  if (fs.existsSync(marker)) {
+    return readJson(marker);
+  }
+
+  const manifest = path.join(dir, "package.json");
+  if (!fs.existsSync(manifest)) {
+    throw new Error(`\`${path.resolve(dir)}\` has no package.json`);
+  }
+  const pkg = readJson(manifest);
+  const deps = { ...pkg.dependencies, ...pkg.devDependencies };
+  if (!deps["copy-webpack-plugin"] && !/^\D*4\./.test(deps.webpack || "")) {
+    throw new Error(`\`${path.resolve(dir)}\` has no ${MARKER}, so it can't tell which version of create-wasm-app made it`);
+  }
+  const files = {};
+  for (const file of fs.readdirSync(LEGACY_DIR)) {
+    files[file] = fs.readFileSync(path.join(LEGACY_DIR, file), "utf8");
+  }
+  return { version: LEGACY_VERSION, options: { name: pkg.name, bundler: "webpack" }, package: null, files };
+}
+
+// Generates what the current template makes of the project described by
+// `marker` into `dest`, returning the options it was generated with.
+function renderProject(dir, marker, dest) {
+  // Options added since the project was made keep their default.
+  const options = { ...resolveOptions([dir], {}), ...marker.options };
+  const vars = varsFor(options);
+  if (marker.package) {
+    vars.wasmPackage = marker.package.name;
+  }
+  scaffold(dest, { layers: layersFor(options), vars });
+  // The project keeps the author it was generated for, rather than whoever
+  // runs `upgrade`.
+  const base = marker.files["package.json"];
+  personalize(dest, base
+    ? { name: options.name, author: JSON.parse(base).author }
+    : { name: options.name });
+  if (marker.package) {
+    replaceDefaultPackage(dest, marker.package);
+  }
+  return options;
+}
+
+const sameJson = (a, b) => JSON.stringify(a) === JSON.stringify(b);
+const isObject = value => typeof value === "object" && value !== null && !Array.isArray(value);
+
+// Three-way merges JSON values: a change made on one side only wins, and
+// when both sides changed a value differently, ours is kept and its path
+// added to `conflicts`. `undefined` stands for a missing key.
+function mergeJson3(base, ours, theirs, key, conflicts) {
+  if (sameJson(ours, theirs) || sameJson(base, theirs)) {
+    return ours;
+  }
+  if (sameJson(base, ours)) {
+    return theirs;
+  }
+  if (isObject(ours) && isObject(theirs)) {
+    const from = isObject(base) ? base : {};
+    const merged = {};
+    for (const k of new Set([...Object.keys(ours), ...Object.keys(theirs)])) {
+      const value = mergeJson3(from[k], ours[k], theirs[k], key ? `${key}.${k}` : k, conflicts);
+      if (value !== undefined) {
+        merged[k] = value;
+      }
+    }
+    return merged;
+  }
+  conflicts.push(key);
+  return ours;
+}
+
+function mergePackageJson(base, ours, theirs) {
+  const conflicts = [];
+  const merged = mergeJson3(JSON.parse(base || "{}"), JSON.parse(ours), JSON.parse(theirs), "", conflicts);
+  for (const field of ["dependencies", "devDependencies"]) {
+    if (merged[field]) {
+      merged[field] = sortKeys(merged[field]);
+    }
+  }
+  return { contents: JSON.stringify(merged, null, 2) + "\n", conflicts };
+}
+
+// Merges text files with `git merge-file`, which leaves conflict markers
+// where both sides changed the same lines.
+function mergeText(base, ours, theirs, from, to) {
+  return withTempDir(tmp => {
+    const files = { ours, base: base || "", theirs };
+    for (const [name, contents] of Object.entries(files)) {
+      fs.writeFileSync(path.join(tmp, name), contents);
+    }
+    const result = git(tmp, [
+      "merge-file", "-p",
+      "-L", "yours", "-L", `create-wasm-app ${from}`, "-L", `create-wasm-app ${to}`,
+      "ours", "base", "theirs",
+    ]);
+    if (result.status > 127) {
+      throw new Error(`\`git merge-file\` failed: ${result.stderr.trim()}`);
+    }
+    return { contents: result.stdout, conflicts: result.status > 0 ? ["conflicting changes"] : [] };
+  });
+}
+
+// A unified diff of the project's files against their upgraded versions.
+function diffChanges(dir, changes) {
+  return withTempDir(tmp => {
+    for (const { file, contents } of changes) {
+      const ours = readFile(path.join(dir, file));
+      for (const [side, text] of [["a", ours], ["b", contents]]) {
+        if (text !== null) {
+          fs.mkdirSync(path.dirname(path.join(tmp, side, file)), { recursive: true });
+          fs.writeFileSync(path.join(tmp, side, file), text);
+        }
+      }
+    }
+    fs.mkdirSync(path.join(tmp, "a"), { recursive: true });
+    return git(tmp, ["-c", "core.quotepath=off", "diff", "--no-index", "--no-prefix", "a", "b"]).stdout;
+  });
+}
+
+// Works out how to bring the project in `dir` up to date with the current
+// template. The template's changes to the managed files since the project
+// was generated are merged into the project's copies, and files the
+// template gained are added. Returns the files to write in `changes`, and
+// in `conflicts` the ones where the project and the template changed the
+// same thing. Nothing is written until `applyUpgrade`.
+function planUpgrade(dir) {
+  const marker = readMarker(dir);
+  return withTempDir(fresh => {
+    const options = renderProject(dir, marker, fresh);
+    const managed = managedFiles(options);
+    const changes = [];
+    const conflicts = [];
+
+    for (const file of managed) {
+      const theirs = readFile(path.join(fresh, file));
+      const ours = readFile(path.join(dir, file));
+      const base = marker.files[file] || null;
+      // A managed file the app's author deleted stays deleted.
+      if (theirs === null || (ours === null && base !== null)) {
+        continue;
+      }
+      const merged = ours === null
+        ? { contents: theirs, conflicts: [] }
+        : file === "package.json"
+          ? mergePackageJson(base, ours, theirs)
+          : mergeText(base, ours, theirs, marker.version, VERSION);
+      if (merged.contents !== ours) {
+        changes.push({ file, contents: merged.contents });
+      }
+      if (merged.conflicts.length > 0) {
+        conflicts.push({ file, conflicts: merged.conflicts });
+      }
+    }
+
+    for (const file of listFiles(fresh)) {
+      if (!managed.includes(file) && !USER_FILES.some(pattern => pattern.test(file)) &&
+          !fs.existsSync(path.join(dir, file))) {
+        changes.push({ file, contents: fs.readFileSync(path.join(fresh, file), "utf8") });
+      }
+    }
+
+    return {
+      from: marker.version,
+      to: VERSION,
+      changes,
+      conflicts,
+      diff: changes.length > 0 ? diffChanges(dir, changes) : "",
+      marker: markerFor(fresh, options, marker.package),
+    };
+  });
+}
+
+// Writes the changes worked out by `planUpgrade` to the project in `dir`,
+// and records the new template version in its marker.
+function applyUpgrade(dir, plan) {
+  for (const { file, contents } of plan.changes) {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), contents);
+  }
+  writeJson(path.join(dir, MARKER), plan.marker);
+}
+
+module.exports = { MARKER, applyUpgrade, planUpgrade, recordPackage, writeMarker };
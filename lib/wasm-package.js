@@ -0,0 +1,252 @@
+const fs = require("fs");
+const path = require("path");
+const { packageExports } = require("./dts");
+const { detectPackageManager, runPackageManager, usesPlugNPlay } = require("./package-manager");
+const { readJson, sortKeys, writeJson } = require("./project");
+
+// The package the template depends on until it is told to use another one.
+const DEFAULT_PACKAGE = "hello-wasm-pack";
+
+const isLocal = spec =>
+  spec.startsWith("file:") || spec.startsWith(".") || path.isAbsolute(spec);
+
+// Turns a `--pkg` argument into the dependency to add to the project in
+// `dir`. It is either an npm package name with an optional version range
+// (`geometry-wasm@^1.2`), or a path to a wasm-pack `pkg/` directory, with or
+// without a `file:` prefix.
+function resolvePackage(spec, dir) {
+  if (isLocal(spec)) {
+    const pkgDir = path.resolve(spec.replace(/^file:/, ""));
+    const manifest = path.join(pkgDir, "package.json");
+    if (!fs.existsSync(manifest)) {
+      throw new Error(`\`${pkgDir}\` has no package.json, has \`wasm-pack build\` been run?`);
+    }
+    const relative = path.relative(path.resolve(dir), pkgDir).split(path.sep).join("/");
+    return {
+      name: readJson(manifest).name,
+      version: `file:${relative.startsWith(".") ? relative : `./${relative}`}`,
+      pkgDir,
+    };
+  }
+
+  const at = spec.indexOf("@", 1);
+  const name = at === -1 ? spec : spec.slice(0, at);
+  const version = at === -1 ? "latest" : spec.slice(at + 1);
+  const installed = [dir, "."]
+    .map(root => path.join(root, "node_modules", name))
+    .find(candidate => fs.existsSync(candidate));
+  return { name, version, pkgDir: installed || null };
+}
+
+// Describes what the package `name` exports in comment lines, pointing at
+// `binding`, the name it is imported as.
+function exportsComment(name, binding, exports) {
+  if (!exports) {
+    return [
+      `// The functions exported by \`${name}\` are declared in its \`.d.ts\``,
+      `// typings; call them through \`${binding}\`.`,
+    ];
+  }
+
+  const { functions, classes } = exports;
+  const lines = [`// \`${name}\` exports:`, "//"];
+  for (const fn of functions) {
+    lines.push(`//   ${fn.name}(${fn.params}): ${fn.returns}`);
+  }
+  for (const cls of classes) {
+    lines.push(`//   class ${cls.name}`);
+  }
+  if (functions.length === 0 && classes.length === 0) {
+    lines.push("//   (nothing)");
+  }
+  lines.push("//", `// Call them through \`${binding}\`.`);
+  return lines;
+}
+
+// An example call of one of the package's functions through `binding`:
+// `greet` when there is one, or else the first one taking no arguments. What
+// it returns is logged. Returns `null` when no function can be called
+// without arguments.
+function exampleCall(binding, exports) {
+  const callable = exports ? exports.functions.filter(fn => fn.params === "") : [];
+  const fn = callable.find(({ name }) => name === "greet") || callable[0];
+  if (!fn) {
+    return null;
+  }
+  const call = `${binding}.${fn.name}()`;
+  return fn.returns === "void" ? `${call};` : `console.log(${call});`;
+}
+
+// Generates an `index.js` that imports the package `name`, listing what it
+// exports when they are known.
+function entrySource(name, exports) {
+  const lines = [
+    `import * as wasm from ${JSON.stringify(name)};`,
+    "",
+    ...exportsComment(name, "wasm", exports),
+  ];
+  const example = exampleCall("wasm", exports);
+  if (example) {
+    lines.push("", example);
+  }
+  return lines.join("\n") + "\n";
+}
+
+// Points the project in `dir` at the wasm package described by `spec` in
+// place of the template's default one, rewriting its `entry` module to use it
+// unless `entry` is `null`. Local packages are found relative to
+// `projectDir`, where the project ends up when `dir` is only where it is put
+// together. Returns the `name` and `version` the project now depends on.
+function usePackage(dir, spec, { entry = "index.js", projectDir = dir } = {}) {
+  const { name, version, pkgDir } = resolvePackage(spec, projectDir);
+  replaceDefaultPackage(dir, { name, version });
+
+  if (entry) {
+    const exports = pkgDir ? packageExports(pkgDir) : null;
+    fs.writeFileSync(path.join(dir, entry), entrySource(name, exports));
+  }
+  return { name, version };
+}
+
+// Finishes setting up the npm package described by `spec` once the project in
+// `dir` has its dependencies installed. `manager` adds it again, so that the
+// project depends on a range of the version it installed, e.g. `^1.2.0`
+// rather than `latest`, with its lockfile to match. The `entry` module then
+// lists what the package exports, unless it was changed since `usePackage`
+// generated it. Returns the `name` and `version` the project now depends on.
+function pinPackage(dir, spec, { entry, manager }) {
+  const { name } = resolvePackage(spec, dir);
+  runPackageManager(manager, "add", dir, [spec]);
+  const version = readJson(path.join(dir, "package.json")).devDependencies[name];
+
+  const entryFile = entry && path.join(dir, entry);
+  if (entryFile && fs.existsSync(entryFile) &&
+      fs.readFileSync(entryFile, "utf8") === entrySource(name, null)) {
+    const exports = packageExports(path.join(dir, "node_modules", name));
+    fs.writeFileSync(entryFile, entrySource(name, exports));
+  }
+  return { name, version };
+}
+
+// Swaps the template's default package for `name@version` in the
+// dependencies of the project in `dir`.
+function replaceDefaultPackage(dir, { name, version }) {
+  const manifest = path.join(dir, "package.json");
+  const pkg = readJson(manifest);
+  delete pkg.devDependencies[DEFAULT_PACKAGE];
+  pkg.devDependencies = sortKeys({ ...pkg.devDependencies, [name]: version });
+  writeJson(manifest, pkg);
+}
+
+// Explains why the npm package in `pkgDir` isn't the output of wasm-pack,
+// or returns `null` when it is: wasm-pack writes the module in a
+// `<crate>_bg.wasm` file, next to the JS bindings the package's entry point
+// loads it with.
+function notWasmPackOutput(pkgDir) {
+  const manifest = path.join(pkgDir, "package.json");
+  if (!fs.existsSync(manifest)) {
+    return "it has no package.json";
+  }
+  if (!fs.readdirSync(pkgDir).some(file => file.endsWith("_bg.wasm"))) {
+    return "it has no `_bg.wasm` file";
+  }
+  const pkg = readJson(manifest);
+  const bindings = pkg.module || pkg.main;
+  if (!bindings || !fs.existsSync(path.join(pkgDir, bindings))) {
+    return "it has no JS bindings";
+  }
+  return null;
+}
+
+// The identifier a package is imported as, e.g. `geometryWasm` for
+// `@shapes/geometry-wasm`.
+function bindingName(name) {
+  const binding = name.replace(/^@[^/]+\//, "")
+    .replace(/[^A-Za-z0-9_$]+(.)?/g, (_, c) => (c ? c.toUpperCase() : ""));
+  return /^[0-9]/.test(binding) ? `_${binding}` : binding;
+}
+
+// Matches the end of an import statement: its module specifier, after `from`
+// or straight after `import` for imports run for their side effects.
+const IMPORT_END = /(?:\bfrom|^import)\s*(["'])[^"']*\1\s*;?\s*(?:\/\/.*)?$/;
+
+// The index of the line after the imports at the top of `lines`. An import
+// can span several lines, e.g. `import {` with a name on each line, and ends
+// with the line holding its module specifier.
+function endOfImports(lines) {
+  let end = 0;
+  let inImport = false;
+  for (const [i, line] of lines.entries()) {
+    if (!inImport) {
+      if (!/^import(?!\s*\()\b/.test(line)) {
+        break;
+      }
+      inImport = true;
+    }
+    if (IMPORT_END.test(line)) {
+      inImport = false;
+      end = i + 1;
+    }
+  }
+  return end;
+}
+
+// Installs the wasm-pack package described by `spec` (see `resolvePackage`)
+// into the existing project in `dir` with its package manager, and imports it
+// from the project's `entry` module. Anything bootstrap.js imports is loaded
+// asynchronously, so a plain `import` is enough there.
+function addPackage(dir, spec, { entry = "index.js" } = {}) {
+  const entryFile = path.join(dir, entry);
+  if (!fs.existsSync(path.join(dir, "package.json")) || !fs.existsSync(entryFile)) {
+    throw new Error(`\`${path.resolve(dir)}\` doesn't look like a project made by create-wasm-app`);
+  }
+  const { name, version } = resolvePackage(spec, dir);
+  const source = fs.readFileSync(entryFile, "utf8");
+  if (source.includes(`from ${JSON.stringify(name)};`)) {
+    throw new Error(`\`${name}\` is already imported in ${entry}`);
+  }
+
+  if (usesPlugNPlay(dir)) {
+    throw new Error("the project's packages are installed with Yarn Plug'n'Play, so there's no node_modules " +
+      "to check the package in; add `nodeLinker: node-modules` to .yarnrc.yml and run `yarn install`");
+  }
+
+  const manager = detectPackageManager(dir);
+  runPackageManager(manager, "add", dir, [isLocal(spec) ? version : spec]);
+  const pkgDir = path.join(dir, "node_modules", name);
+  const reason = notWasmPackOutput(pkgDir);
+  if (reason) {
+    runPackageManager(manager, "remove", dir, [name]);
+    throw new Error(`\`${name}\` isn't a package built by wasm-pack: ${reason}`);
+  }
+
+  const binding = bindingName(name);
+  const statement = `import * as ${binding} from ${JSON.stringify(name)};`;
+  const lines = source.split("\n");
+  const imports = endOfImports(lines);
+  if (imports === 0) {
+    lines.unshift(statement, "");
+  } else {
+    lines.splice(imports, 0, statement);
+  }
+  const exports = packageExports(pkgDir);
+  const usage = exportsComment(name, binding, exports);
+  const example = exampleCall(binding, exports);
+  if (example) {
+    usage.push("", example);
+  }
+  const body = lines.join("\n").replace(/\n*$/, "\n");
+  fs.writeFileSync(entryFile, `${body}\n${usage.join("\n")}\n`);
+  return { name, binding };
+}
+
+module.exports = {
+  DEFAULT_PACKAGE,
+  addPackage,
+  entrySource,
+  isLocal,
+  pinPackage,
+  replaceDefaultPackage,
+  resolvePackage,
+  usePackage,
+};
@@ -0,0 +1,152 @@
+// Checks the size of each `.wasm` and `.js` file in `dist/` as the last step
+// of `build:prod`, and fails the build when one is over its budget. Budgets are
+// set by the `sizeBudgets` field of package.json, from patterns of the files'
+// paths in `dist/` (where `*` matches anything) to their largest `raw`, `gzip`
+// and `brotli` sizes, in bytes or like "96 KiB":
+//
+//   "sizeBudgets": { "*.wasm": { "raw": "512 KiB", "gzip": "192 KiB" } }
+//
+// The sizes are written to build-sizes.json; commit it to see how a change
+// affects them. Each build compares the total sizes with the previous report.
+
+const fs = require("fs");
+const path = require("path");
+const zlib = require("zlib");
+
+const projectDir = path.join(__dirname, "..");
+const distDir = path.join(projectDir, "dist");
+const REPORT = path.join(projectDir, "build-sizes.json");
+const KINDS = ["raw", "gzip", "brotli"];
+const UNITS = { B: 1, kB: 1000, KiB: 1024, MB: 1000 ** 2, MiB: 1024 ** 2 };
+
+function assets(dir, prefix = "") {
+  return fs.readdirSync(dir, { withFileTypes: true }).flatMap(entry => {
+    const name = prefix + entry.name;
+    if (entry.isDirectory()) {
+      return assets(path.join(dir, entry.name), `${name}/`);
+    }
+    return /\.(wasm|js)$/.test(name) ? [name] : [];
+  });
+}
+
+function measure(file) {
+  const contents = fs.readFileSync(file);
+  return {
+    raw: contents.length,
+    gzip: zlib.gzipSync(contents, { level: 9 }).length,
+    brotli: zlib.brotliCompressSync(contents, {
+      params: {
+        [zlib.constants.BROTLI_PARAM_QUALITY]: zlib.constants.BROTLI_MAX_QUALITY,
+        [zlib.constants.BROTLI_PARAM_SIZE_HINT]: contents.length,
+      },
+    }).length,
+  };
+}
+
+function parseSize(size, where) {
+  if (typeof size === "number") {
+    return size;
+  }
+  const match = /^(\d+(?:\.\d+)?)\s*(B|kB|KiB|MB|MiB)$/.exec(size);
+  if (!match) {
+    throw new Error(`${where} is ${JSON.stringify(size)}; write a number of bytes or a size like "96 KiB"`);
+  }
+  return Math.round(Number(match[1]) * UNITS[match[2]]);
+}
+
+// `*` in a pattern matches anything, and everything else itself.
+function patternRegExp(pattern) {
+  const parts = pattern.split("*").map(part => part.replace(/[.+?^${}()|[\]\\]/g, "\\$&"));
+  return new RegExp(`^${parts.join(".*")}$`);
+}
+
+// The smallest budget of each kind that applies to `name`.
+function budgetFor(name, budgets) {
+  const budget = {};
+  for (const [pattern, sizes] of Object.entries(budgets)) {
+    if (!patternRegExp(pattern).test(name)) {
+      continue;
+    }
+    for (const [kind, size] of Object.entries(sizes)) {
+      if (!KINDS.includes(kind)) {
+        throw new Error(`sizeBudgets["${pattern}"] has ${kind}, but budgets can only be ${KINDS.join(", ")}`);
+      }
+      budget[kind] = Math.min(budget[kind] ?? Infinity, parseSize(size, `sizeBudgets["${pattern}"].${kind}`));
+    }
+  }
+  return budget;
+}
+
+const kib = bytes => `${(bytes / 1024).toFixed(1)} KiB`;
+const signed = bytes => `${bytes < 0 ? "-" : "+"}${kib(Math.abs(bytes))}`;
+
+// The total sizes of the `.wasm` files and of the `.js` files.
+function totals(files) {
+  const sums = {};
+  for (const { name, sizes } of files) {
+    const type = path.extname(name).slice(1);
+    sums[type] = sums[type] || { raw: 0, gzip: 0, brotli: 0 };
+    for (const kind of KINDS) {
+      sums[type][kind] += sizes[kind];
+    }
+  }
+  return sums;
+}
+
+function printTable(files) {
+  const rows = [["Asset", ...KINDS]];
+  for (const { name, sizes, budget } of files) {
+    rows.push([name, ...KINDS.map(kind => {
+      const cell = kib(sizes[kind]);
+      return sizes[kind] > budget[kind] ? `${cell} ✖ (budget ${kib(budget[kind])})` : cell;
+    })]);
+  }
+  const widths = rows[0].map((_, column) => Math.max(...rows.map(row => row[column].length)));
+  for (const row of rows) {
+    console.log(`  ${row.map((cell, column) => cell.padEnd(widths[column])).join("  ").trimEnd()}`);
+  }
+}
+
+function main() {
+  const pkg = JSON.parse(fs.readFileSync(path.join(projectDir, "package.json"), "utf8"));
+  const budgets = pkg.sizeBudgets || {};
+  const names = fs.existsSync(distDir) ? assets(distDir).sort() : [];
+  const files = names.map(name => ({
+    name,
+    sizes: measure(path.join(distDir, name)),
+    budget: budgetFor(name, budgets),
+  }));
+
+  console.log("Sizes of dist/:");
+  printTable(files);
+
+  const report = {
+    files: Object.fromEntries(files.map(({ name, sizes }) => [name, sizes])),
+    totals: totals(files),
+  };
+  if (fs.existsSync(REPORT)) {
+    const previous = JSON.parse(fs.readFileSync(REPORT, "utf8")).totals || {};
+    for (const [type, sums] of Object.entries(report.totals)) {
+      const before = previous[type];
+      if (before) {
+        const changes = KINDS.map(kind => `${kind} ${signed(sums[kind] - before[kind])}`);
+        console.log(`Total .${type} since the last report: ${changes.join(", ")}`);
+      }
+    }
+  }
+  fs.writeFileSync(REPORT, JSON.stringify(report, null, 2) + "\n");
+
+  const over = files.filter(({ sizes, budget }) => KINDS.some(kind => sizes[kind] > budget[kind]));
+  if (over.length > 0) {
+    console.error(`Over budget: ${over.map(({ name }) => name).join(", ")}`);
+    return 1;
+  }
+  return 0;
+}
+
+try {
+  process.exit(main());
+} catch (e) {
+  console.error(e.message);
+  process.exit(1);
+}
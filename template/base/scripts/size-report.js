@@ -0,0 +1,150 @@
+// Attributes the size of each `.wasm` file in `dist/` to the functions and
+// data in it, with twiggy (`cargo install twiggy`):
+//
+// - `top`: the biggest items, by their own size;
+// - `dominators`: the items that keep others in the binary, by the size
+//   that would go with them;
+// - `monos`: the generic functions whose monomorphizations cost the most.
+//
+// The results go to size-report/wasm.json, and to size-report/wasm.html
+// along with the bundler's own analysis when it has one. Items are named
+// after the wasm's `name` section, which optimising without `-g` strips.
+
+const { spawnSync } = require("child_process");
+const fs = require("fs");
+const path = require("path");
+
+const projectDir = path.join(__dirname, "..");
+const distDir = path.join(projectDir, "dist");
+const reportDir = path.join(projectDir, "size-report");
+
+const ANALYSES = {
+  top: ["top", "--max-items", "100"],
+  dominators: ["dominators", "--max-depth", "6", "--max-rows", "200"],
+  monos: ["monos", "--max-generics", "50", "--max-monos", "10"],
+};
+
+function wasmFiles(dir, prefix = "") {
+  return fs.readdirSync(dir, { withFileTypes: true }).flatMap(entry => {
+    const name = prefix + entry.name;
+    if (entry.isDirectory()) {
+      return wasmFiles(path.join(dir, entry.name), `${name}/`);
+    }
+    return name.endsWith(".wasm") ? [name] : [];
+  });
+}
+
+function twiggy(args, file) {
+  const result = spawnSync("twiggy", [...args, "--format", "json", file], {
+    encoding: "utf8",
+    stdio: ["ignore", "pipe", "inherit"],
+    maxBuffer: 64 * 1024 * 1024,
+  });
+  if (result.error) {
+    throw new Error(`couldn't run twiggy: ${result.error.message}\nInstall it with \`cargo install twiggy\`.`);
+  }
+  if (result.status !== 0) {
+    throw new Error(`\`twiggy ${args[0]}\` failed on ${path.relative(projectDir, file)}`);
+  }
+  return JSON.parse(result.stdout);
+}
+
+// twiggy's JSON output is a list of items, or an object listing them under
+// `items`.
+const itemsOf = output => (Array.isArray(output) ? output : output.items || []);
+
+function hasNames(file) {
+  const module = new WebAssembly.Module(fs.readFileSync(file));
+  return WebAssembly.Module.customSections(module, "name").length > 0;
+}
+
+const escape = text => String(text).replace(/[&<>"]/g, c => `&#${c.charCodeAt(0)};`);
+const kib = bytes => `${(bytes / 1024).toFixed(1)} KiB`;
+const percent = value => `${Number(value).toFixed(2)}%`;
+
+function table(headings, rows) {
+  const cells = (tag, values) => values.map(value => `<${tag}>${escape(value)}</${tag}>`).join("");
+  const body = rows.map(row => `<tr>${cells("td", row)}</tr>`).join("");
+  return `<table><tr>${cells("th", headings)}</tr>${body}</table>`;
+}
+
+function dominatorTree(items) {
+  return `<ul>${items.map(item => {
+    const label = `${kib(item.retained_size)} (${percent(item.retained_size_percent)}) ${item.name}`;
+    return item.children && item.children.length > 0
+      ? `<li><details><summary>${escape(label)}</summary>${dominatorTree(item.children)}</details></li>`
+      : `<li>${escape(label)}</li>`;
+  }).join("")}</ul>`;
+}
+
+function html(reports) {
+  const bundle = fs.existsSync(path.join(reportDir, "bundle.html"))
+    ? `<p>See also the <a href="bundle.html">bundle analysis</a>.</p>`
+    : "";
+  const sections = reports.map(({ file, size, named, top, dominators, monos }) => `
+<h2>${escape(file)} (${kib(size)})</h2>
+${named ? "" : "<p><strong>No <code>name</code> section: items are only numbered.</strong></p>"}
+<h3>Top items</h3>
+${table(["Size", "%", "Item"], itemsOf(top).map(item =>
+    [kib(item.shallow_size), percent(item.shallow_size_percent), item.name]))}
+<h3>Dominators</h3>
+${dominatorTree(itemsOf(dominators))}
+<h3>Monomorphizations</h3>
+${table(["Bloat", "%", "Total", "Generic", "Monomorphizations"], itemsOf(monos).map(item => [
+    kib(item.approximate_monomorphization_bloat_size),
+    percent(item.approximate_monomorphization_bloat_size_percent),
+    kib(item.total_size),
+    item.generic,
+    (item.monomorphizations || []).length,
+  ]))}`);
+  return `<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <title>Wasm size report</title>
+    <style>
+      body { font-family: sans-serif; margin: 2em; }
+      table { border-collapse: collapse; }
+      th, td { padding: 0.2em 0.6em; text-align: left; font-family: monospace; }
+      tr:nth-child(even) { background: #f4f4f4; }
+      ul { font-family: monospace; list-style: none; }
+    </style>
+  </head>
+  <body>
+    <h1>Wasm size report</h1>
+    ${bundle}${sections.join("\n")}
+  </body>
+</html>
+`;
+}
+
+function main() {
+  const files = fs.existsSync(distDir) ? wasmFiles(distDir).sort() : [];
+  if (files.length === 0) {
+    throw new Error("there are no .wasm files in dist/ to report on");
+  }
+  const reports = files.map(name => {
+    const file = path.join(distDir, name);
+    const named = hasNames(file);
+    if (!named) {
+      console.warn(`${name} has no \`name\` section, so twiggy can only number its items; ` +
+        "build the wasm package with `wasm-pack build --profiling` to keep it.");
+    }
+    const analyses = Object.fromEntries(Object.entries(ANALYSES)
+      .map(([analysis, args]) => [analysis, twiggy(args, file)]));
+    return { file: name, size: fs.statSync(file).size, named, ...analyses };
+  });
+
+  fs.mkdirSync(reportDir, { recursive: true });
+  fs.writeFileSync(path.join(reportDir, "wasm.json"), JSON.stringify(reports, null, 2) + "\n");
+  fs.writeFileSync(path.join(reportDir, "wasm.html"), html(reports));
+  console.log("Wrote size-report/wasm.html and size-report/wasm.json");
+  return 0;
+}
+
+try {
+  process.exit(main());
+} catch (e) {
+  console.error(e.message);
+  process.exit(1);
+}
@@ -0,0 +1,77 @@
+// Runs Binaryen's wasm-opt over every `.wasm` file in `dist/` once
+// `build:prod` has bundled the app, and reports how much each one shrank. It
+// is set up by the `wasmOpt` field of package.json:
+//
+// - `level`: the optimisation level, `-O1` to `-O4`, `-Os` or `-Oz`;
+// - `features`: the WebAssembly proposals the `.wasm` files may use, which
+//   wasm-opt has to be told about, e.g. `simd` for `--enable-simd`.
+//
+// Set `"wasmOpt": false`, or build with `WASM_OPT=false`, to ship the `.wasm`
+// files as the bundler emitted them.
+
+const { spawnSync } = require("child_process");
+const fs = require("fs");
+const path = require("path");
+
+const projectDir = path.join(__dirname, "..");
+const distDir = path.join(projectDir, "dist");
+const LEVELS = ["-O", "-O0", "-O1", "-O2", "-O3", "-O4", "-Os", "-Oz"];
+
+function wasmFiles(dir) {
+  return fs.readdirSync(dir, { withFileTypes: true }).flatMap(entry => {
+    const file = path.join(dir, entry.name);
+    if (entry.isDirectory()) {
+      return wasmFiles(file);
+    }
+    return entry.name.endsWith(".wasm") ? [file] : [];
+  });
+}
+
+const kib = bytes => `${(bytes / 1024).toFixed(1)} KiB`;
+
+function main() {
+  const pkg = JSON.parse(fs.readFileSync(path.join(projectDir, "package.json"), "utf8"));
+  if (pkg.wasmOpt === false || process.env.WASM_OPT === "false") {
+    console.log("wasm-opt: skipped");
+    return 0;
+  }
+  const { level = "-Os", features = [] } = pkg.wasmOpt || {};
+  if (!LEVELS.includes(level)) {
+    console.error(`wasm-opt: \`wasmOpt.level\` is ${level}, but has to be one of ${LEVELS.join(", ")}`);
+    return 1;
+  }
+  const flags = [level, ...features.map(feature => `--enable-${feature}`)];
+
+  const sizes = [];
+  for (const file of fs.existsSync(distDir) ? wasmFiles(distDir) : []) {
+    const name = path.relative(projectDir, file);
+    const before = fs.statSync(file).size;
+    // wasm-opt comes from the `binaryen` package, in node_modules/.bin, which
+    // is a batch file on Windows that Node only runs through a shell.
+    const result = spawnSync("wasm-opt", [file, "-o", file, ...flags], {
+      stdio: "inherit",
+      shell: process.platform === "win32",
+    });
+    if (result.error || result.status !== 0) {
+      console.error(result.error
+        ? `wasm-opt: couldn't run it: ${result.error.message}`
+        : `wasm-opt: failed to optimise ${name}`);
+      return 1;
+    }
+    sizes.push({ name, before, after: fs.statSync(file).size });
+  }
+
+  if (sizes.length === 0) {
+    console.log("wasm-opt: no .wasm files in dist/");
+    return 0;
+  }
+  const width = Math.max(...sizes.map(({ name }) => name.length));
+  console.log(`wasm-opt ${level}:`);
+  for (const { name, before, after } of sizes) {
+    const change = ((after - before) / before * 100).toFixed(1);
+    console.log(`  ${name.padEnd(width)}  ${kib(before)} → ${kib(after)} (${change}%)`);
+  }
+  return 0;
+}
+
+process.exit(main());
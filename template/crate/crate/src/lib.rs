@@ -0,0 +1,51 @@
+{{#if threads}}
+use rayon::prelude::*;
+{{/if}}
+use wasm_bindgen::prelude::*;
+
+{{#if threads}}
+// Exports `initThreadPool`, which bootstrap.js calls to start the Web Workers
+// rayon runs its tasks on.
+pub use wasm_bindgen_rayon::init_thread_pool;
+
+{{/if}}
+{{#if !worker}}
+#[wasm_bindgen]
+extern "C" {
+    fn alert(s: &str);
+}
+
+{{/if}}
+// Runs once, when the wasm module is instantiated.
+#[wasm_bindgen(start)]
+pub fn start() {
+    // When the `console_error_panic_hook` feature is enabled, we can call the
+    // `set_once` function at least once during initialization, and then we
+    // will get better error messages if our code ever panics.
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+}
+
+{{#if worker}}
+// Runs in a Web Worker, which has no `alert`: the page shows the greeting.
+#[wasm_bindgen]
+pub fn greet() -> String {
+    "Hello, {{crateName}}!".into()
+}
+{{else}}
+#[wasm_bindgen]
+pub fn greet() {
+    alert("Hello, {{crateName}}!");
+}
+{{/if}}
+{{#if threads}}
+
+// Sums the squares of `numbers` on every thread of the pool.
+//
+// Like anything else that waits for other threads, this blocks, which
+// browsers only allow off the main thread: call it from a Web Worker.
+#[wasm_bindgen]
+pub fn sum_of_squares(numbers: &[i32]) -> i32 {
+    numbers.par_iter().map(|x| x * x).sum()
+}
+{{/if}}
@@ -0,0 +1,71 @@
+// Rebuilds the crate when its sources change while the dev server runs; the
+// bundler config hooks this into the dev server's watcher. The build is the
+// project's `build:wasm` script in wasm-pack's dev profile, which is quicker
+// than a release build and keeps debug assertions on.
+
+const { spawn } = require("child_process");
+const fs = require("fs");
+const path = require("path");
+
+const projectDir = path.join(__dirname, "..");
+const crateDir = path.join(projectDir, "crate");
+
+// Whether a change to `file` calls for rebuilding the crate.
+function isCrateSource(file) {
+  const relative = path.relative(crateDir, file);
+  return relative === "Cargo.toml" ||
+    (relative.startsWith(`src${path.sep}`) && relative.endsWith(".rs"));
+}
+
+function runBuild() {
+  const pkg = JSON.parse(fs.readFileSync(path.join(projectDir, "package.json"), "utf8"));
+  return new Promise(resolve => {
+    const child = spawn(`${pkg.scripts["build:wasm"]} --dev`, {
+      cwd: projectDir,
+      shell: true,
+      stdio: ["ignore", "pipe", "pipe"],
+    });
+    let output = "";
+    for (const stream of [child.stdout, child.stderr]) {
+      stream.on("data", data => {
+        output += data;
+        process.stderr.write(data);
+      });
+    }
+    child.on("error", e => resolve(e.message));
+    // eslint-disable-next-line no-control-regex
+    child.on("close", code => resolve(code === 0 ? null : output.replace(/\x1b\[[0-9;]*m/g, "")));
+  });
+}
+
+let queue = Promise.resolve(null);
+
+// Builds the crate after any build already running. Resolves to `null` when
+// it succeeds, or to cargo's and wasm-pack's output when it fails.
+function buildCrate() {
+  queue = queue.then(runBuild);
+  return queue;
+}
+
+// The source of a module showing `error`, what a failed build of the crate
+// printed, over the page, or doing nothing when the crate built. Bundlers
+// whose dev servers have no overlay of their own bundle it into the page.
+function errorPageSource(error) {
+  return `const error = ${JSON.stringify(error)};
+if (error) {
+  const overlay = document.createElement("div");
+  overlay.setAttribute("role", "alert");
+  overlay.style.cssText = "position: fixed; inset: 0; overflow: auto; " +
+    "padding: 2rem; background: #fff; color: #222; font-family: sans-serif;";
+  const heading = document.createElement("h1");
+  heading.textContent = "The crate failed to build";
+  const details = document.createElement("pre");
+  details.style.whiteSpace = "pre-wrap";
+  details.textContent = error;
+  overlay.append(heading, details);
+  document.body.append(overlay);
+}
+`;
+}
+
+module.exports = { buildCrate, crateDir, errorPageSource, isCrateSource };
@@ -0,0 +1,112 @@
+import * as esbuild from "esbuild";
+import { wasmLoader } from "esbuild-plugin-wasm";
+import { {{#if pwa}}cp, {{/if}}mkdir, readFile, rm, writeFile } from "node:fs/promises";
+{{#if crate}}
+import { readdirSync, watch } from "node:fs";
+import path from "node:path";
+import { buildCrate, crateDir, errorPageSource, isCrateSource } from "./scripts/crate-dev.js";
+{{/if}}
+
+// `node esbuild.config.mjs` builds the development profile into `dist/`,
+// `--production` builds a minified one, and `--serve` rebuilds on change and
+// serves `dist/` on http://localhost:8080.
+const production = process.argv.includes("--production");
+const serve = process.argv.includes("--serve");
+{{#if crate}}
+
+// What the last build of the crate printed when it failed.
+let crateError = null;
+{{/if}}
+
+// dev-client.js, which index.html loads when serving, reloads the page when
+// esbuild has rebuilt it{{#if crate}}, and shows the crate's build errors over it{{/if}}.
+const devClient = {
+  name: "dev-client",
+  setup(build) {
+    build.onResolve({ filter: /^dev-client$/ }, () => ({ path: "dev-client", namespace: "dev-client" }));
+    build.onLoad({ filter: /.*/, namespace: "dev-client" }, () => ({
+      contents: `new EventSource("/esbuild").addEventListener("change", () => location.reload());\n`{{#if crate}} +
+        errorPageSource(crateError){{/if}},
+    }));
+  },
+};
+
+const options = {
+  entryPoints: {
+    bootstrap: "bootstrap.{{scriptExt}}",
+    ...(serve ? { "dev-client": "dev-client" } : {}),
+  },
+  outdir: "dist",
+  bundle: true,
+  // `import()` of index.js becomes its own chunk, like with other bundlers.
+  format: "esm",
+  splitting: true,
+  // wasm-pack's output is instantiated with a top-level await.
+  target: "es2022",
+  minify: production,
+  sourcemap: true,
+  // Older wasm-pack output, like hello-wasm-pack's, imports its `.wasm` file
+  // without the extension.
+  resolveExtensions: [{{#if typescript}}".ts", {{/if}}".mjs", ".js", ".json", ".wasm"],
+  define: {
+    // Shows why the app failed to load on the page itself, unless built with
+    // `WASM_ERROR_OVERLAY=false`.
+    __ERROR_OVERLAY__: JSON.stringify(process.env.WASM_ERROR_OVERLAY !== "false"),
+  },
+  plugins: [
+    // Lets wasm-pack's output import its `.wasm` file like any other module;
+    // it is loaded asynchronously, behind the `import()` in bootstrap.js.
+    wasmLoader(),
+    ...(serve ? [devClient] : []),
+  ],
+};
+
+await rm("dist", { recursive: true, force: true });
+await mkdir("dist");
+const html = await readFile("index.html", "utf8");
+await writeFile("dist/index.html", serve
+  ? html.replace("</body>", `  <script type="module" src="./dev-client.js"></script>\n  </body>`)
+  : html);
+{{#if pwa}}
+// The web app manifest, its icon and the service worker.
+await cp("public", "dist", { recursive: true });
+{{/if}}
+
+if (serve) {
+  const context = await esbuild.context(options);
+  await context.watch();
+{{#if crate}}
+  // Rebuilds the crate with wasm-pack when its sources change, then the
+  // bundle: with the new package when the crate built, or with dev-client.js
+  // showing cargo's errors when it didn't. Either way, the page reloads.
+  // Each directory is watched on its own, since Node only watches them
+  // recursively on Linux from 19.1, and files through their directory, since
+  // editors often save them by replacing them.
+  const rebuildCrate = async file => {
+    if (!isCrateSource(file)) {
+      return;
+    }
+    crateError = await buildCrate();
+    try {
+      await context.rebuild();
+    } catch {
+      // The bundle failed to build, e.g. because index.js imports something
+      // the crate no longer exports; esbuild has printed why.
+    }
+  };
+  const watchDir = dir => {
+    watch(dir, (event, file) => file && rebuildCrate(path.join(dir, file)));
+    for (const entry of readdirSync(dir, { withFileTypes: true })) {
+      if (entry.isDirectory()) {
+        watchDir(path.join(dir, entry.name));
+      }
+    }
+  };
+  watch(crateDir, (event, file) => file && rebuildCrate(path.join(crateDir, file)));
+  watchDir(path.join(crateDir, "src"));
+{{/if}}
+  const { port } = await context.serve({ servedir: "dist", port: 8080 });
+  console.log(`Serving on http://localhost:${port}`);
+} else {
+  await esbuild.build(options);
+}
@@ -0,0 +1,46 @@
+// The service worker that makes the app work offline. Requests for the app's
+// own files go to the network first, so that a new deployment is picked up
+// right away, and their responses are kept in a cache to answer with when
+// the network can't be reached. Bump `CACHE` to drop what older versions of
+// the app cached.
+
+const CACHE = "app-v1";
+
+self.addEventListener("install", () => {
+  self.skipWaiting();
+});
+
+self.addEventListener("activate", event => {
+  event.waitUntil((async () => {
+    for (const name of await caches.keys()) {
+      if (name !== CACHE) {
+        await caches.delete(name);
+      }
+    }
+    await self.clients.claim();
+  })());
+});
+
+async function networkFirst(request) {
+  const cache = await caches.open(CACHE);
+  try {
+    const response = await fetch(request);
+    if (response.ok) {
+      await cache.put(request, response.clone());
+    }
+    return response;
+  } catch (e) {
+    const cached = await cache.match(request);
+    if (cached) {
+      return cached;
+    }
+    throw e;
+  }
+}
+
+self.addEventListener("fetch", event => {
+  const { request } = event;
+  if (request.method === "GET" && new URL(request.url).origin === self.location.origin) {
+    event.respondWith(networkFirst(request));
+  }
+});
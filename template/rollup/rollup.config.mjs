@@ -0,0 +1,174 @@
+import { nodeResolve } from "@rollup/plugin-node-resolve";
+import replace from "@rollup/plugin-replace";
+import terser from "@rollup/plugin-terser";
+{{#if typescript}}
+import typescript from "@rollup/plugin-typescript";
+{{/if}}
+import { wasm } from "@rollup/plugin-wasm";
+import { readFile, rm } from "node:fs/promises";
+import copy from "rollup-plugin-copy";
+import livereload from "rollup-plugin-livereload";
+import serve from "rollup-plugin-serve";
+{{#if crate}}
+import { buildCrate, crateDir, errorPageSource, isCrateSource } from "./scripts/crate-dev.js";
+{{/if}}
+
+// `rollup -c` builds the development profile into `dist/`,
+// `--environment BUILD:production` builds a minified one, and
+// `--watch --environment SERVE` rebuilds on change and serves `dist/` on
+// http://localhost:8080.
+const production = process.env.BUILD === "production";
+const dev = Boolean(process.env.SERVE);
+
+// Rollup only ever adds to `dist/`, and chunks and `.wasm` files are named
+// after their contents, so what earlier builds emitted is removed first.
+await rm("dist", { recursive: true, force: true });
+
+const WRAPPED = "\0wasm-esm:";
+
+// wasm-pack's output imports its `.wasm` file as an ES module, the way the
+// WebAssembly ESM integration proposal describes. @rollup/plugin-wasm gives
+// back a function that instantiates the module instead, so each `.wasm`
+// import is routed through a small module that instantiates it with a
+// top-level await, passing in the modules it imports, and re-exports what
+// the instance exports.
+function wasmEsmIntegration() {
+  return {
+    name: "wasm-esm-integration",
+
+    async resolveId(source, importer) {
+      if (importer && importer.startsWith(WRAPPED)) {
+        // The wrapper's own imports are relative to the `.wasm` file.
+        const file = importer.slice(WRAPPED.length);
+        return source === file ? null : this.resolve(source, file, { skipSelf: true });
+      }
+      const resolved = await this.resolve(source, importer, { skipSelf: true });
+      if (resolved && resolved.id.endsWith(".wasm")) {
+        return WRAPPED + resolved.id;
+      }
+      return null;
+    },
+
+    async load(id) {
+      if (!id.startsWith(WRAPPED)) {
+        return null;
+      }
+      const file = id.slice(WRAPPED.length);
+      const module = await WebAssembly.compile(await readFile(file));
+      const imports = [...new Set(WebAssembly.Module.imports(module).map(i => i.module))];
+      const exports = WebAssembly.Module.exports(module)
+        .map(e => e.name)
+        .filter(name => /^[A-Za-z_$][\w$]*$/.test(name));
+
+      return [
+        `import init from ${JSON.stringify(file)};`,
+        ...imports.map((name, i) => `import * as import${i} from ${JSON.stringify(name)};`),
+        `const { instance } = await init({`,
+        ...imports.map((name, i) => `  ${JSON.stringify(name)}: import${i},`),
+        `});`,
+        ...exports.map(name => `export const ${name} = instance.exports.${name};`),
+      ].join("\n");
+    },
+  };
+}
+
+{{#if crate}}
+const DEV_CLIENT = "crate-dev-client";
+
+// Rebuilds the crate with wasm-pack when its sources change under
+// `--watch`. When the build fails, cargo's errors are bundled into
+// dev-client.js, which shows them over the page; once the crate builds, the
+// bundle is rebuilt with it. Either way, the page reloads.
+function crateWatch() {
+  let changed = false;
+  let error = null;
+  return {
+    name: "crate-watch",
+
+    async buildStart() {
+      this.addWatchFile(`${crateDir}/src`);
+      this.addWatchFile(`${crateDir}/Cargo.toml`);
+      if (changed) {
+        changed = false;
+        error = await buildCrate();
+      }
+    },
+
+    resolveId(source) {
+      return source === DEV_CLIENT ? `\0${DEV_CLIENT}` : null;
+    },
+
+    load(id) {
+      return id === `\0${DEV_CLIENT}` ? errorPageSource(error) : null;
+    },
+
+    watchChange(id) {
+      changed = changed || isCrateSource(id);
+    },
+  };
+}
+
+// Loads dev-client.js from index.html when serving.
+const withDevClient = contents => (dev
+  ? contents.toString().replace("</body>", `  <script type="module" src="./dev-client.js"></script>\n  </body>`)
+  : contents);
+
+{{/if}}
+export default {
+{{#if crate}}
+  input: dev
+    ? { bootstrap: "bootstrap.{{scriptExt}}", "dev-client": DEV_CLIENT }
+    : "bootstrap.{{scriptExt}}",
+{{else}}
+  input: "bootstrap.{{scriptExt}}",
+{{/if}}
+  output: {
+    dir: "dist",
+    // `import()` of index.js becomes its own chunk, like with other bundlers.
+    format: "es",
+    sourcemap: true,
+  },
+  plugins: [
+    // Has to come first, to see `.wasm` imports before they are resolved.
+    wasmEsmIntegration(),
+    nodeResolve({
+      // Older wasm-pack output, like hello-wasm-pack's, imports its `.wasm`
+      // file without the extension.
+      extensions: [{{#if typescript}}".ts", {{/if}}".mjs", ".js", ".json", ".wasm"],
+    }),
+{{#if typescript}}
+    typescript(),
+{{/if}}
+    replace({
+      preventAssignment: true,
+      values: {
+        // Shows why the app failed to load on the page itself, unless built
+        // with `WASM_ERROR_OVERLAY=false`.
+        __ERROR_OVERLAY__: JSON.stringify(process.env.WASM_ERROR_OVERLAY !== "false"),
+      },
+    }),
+    wasm({
+      targetEnv: "browser",
+      // Always emit `.wasm` files next to the bundle rather than inlining
+      // them as base64.
+      maxFileSize: 0,
+    }),
+    copy({
+{{#if pwa}}
+      targets: [
+        { src: "index.html", dest: "dist"{{#if crate}}, transform: withDevClient{{/if}} },
+        // The web app manifest, its icon and the service worker.
+        { src: "public/*", dest: "dist" },
+      ],
+{{else}}
+      targets: [{ src: "index.html", dest: "dist"{{#if crate}}, transform: withDevClient{{/if}} }],
+{{/if}}
+    }),
+    production && terser(),
+{{#if crate}}
+    dev && crateWatch(),
+{{/if}}
+    dev && serve({ contentBase: "dist", port: 8080 }),
+    dev && livereload("dist"),
+  ],
+};
@@ -0,0 +1,35 @@
+// Builds the crate twice with wasm-pack: into `crate/pkg` for every browser,
+// and into `crate/pkg-simd` with SIMD instructions for the browsers that
+// support them. Arguments are passed on to `wasm-pack build`, e.g. `--dev`.
+
+const { spawnSync } = require("child_process");
+const fs = require("fs");
+const path = require("path");
+
+const crateDir = path.join(__dirname, "..", "crate");
+
+function build(outDir, rustflags) {
+  const env = { ...process.env };
+  if (rustflags) {
+    env.RUSTFLAGS = [process.env.RUSTFLAGS, rustflags].filter(Boolean).join(" ");
+    // Keeps cargo from rebuilding everything whenever the flags change
+    // between the two builds.
+    env.CARGO_TARGET_DIR = path.join(crateDir, "target", outDir);
+  }
+  const args = ["build", crateDir, "--out-dir", outDir, ...process.argv.slice(2)];
+  const result = spawnSync("wasm-pack", args, { stdio: "inherit", env });
+  if (result.error || result.status !== 0) {
+    console.error(result.error ? `couldn't run wasm-pack: ${result.error.message}` : "wasm-pack failed");
+    process.exit(1);
+  }
+}
+
+build("pkg");
+build("pkg-simd", "-C target-feature=+simd128");
+
+// Both builds are named after the crate; the SIMD one is renamed so that the
+// app can depend on both.
+const manifest = path.join(crateDir, "pkg-simd", "package.json");
+const pkg = JSON.parse(fs.readFileSync(manifest, "utf8"));
+pkg.name = `${pkg.name}-simd`;
+fs.writeFileSync(manifest, JSON.stringify(pkg, null, 2) + "\n");
@@ -0,0 +1,105 @@
+import { defineConfig } from "vite";
+import wasm from "vite-plugin-wasm";
+{{#if crate}}
+import path from "path";
+import { buildCrate, crateDir, isCrateSource } from "./scripts/crate-dev.js";
+
+// Rebuilds the crate with wasm-pack when its sources change under the dev
+// server. Cargo's errors show in Vite's overlay, and the page reloads once
+// the crate builds.
+function crateWatch() {
+  return {
+    name: "crate-watch",
+    apply: "serve",
+    configureServer(server) {
+      server.watcher.add([path.join(crateDir, "src"), path.join(crateDir, "Cargo.toml")]);
+      server.watcher.on("change", async file => {
+        if (!isCrateSource(file)) {
+          return;
+        }
+        const error = await buildCrate();
+        if (error) {
+          server.ws.send({
+            type: "error",
+            err: { plugin: "crate-watch", message: "Building the crate failed", frame: error, stack: "" },
+          });
+        } else {
+          server.ws.send({ type: "full-reload" });
+        }
+      });
+    },
+  };
+}
+{{/if}}
+{{#if worker}}
+import { generateWorkerProxy } from "./scripts/generate-worker-proxy.js";
+
+// Generates wasm-proxy.{{scriptExt}} from the wasm package's typings before
+// the app is built or served, so that it has a function for everything the
+// package exports.
+function workerProxy() {
+  return {
+    name: "worker-proxy",
+    buildStart() {
+      generateWorkerProxy();
+    },
+  };
+}
+{{/if}}
+
+export default defineConfig({
+  plugins: [
+    // Lets wasm-pack's output import its `.wasm` file like any other module;
+    // it is loaded asynchronously, behind the `import()` in bootstrap.js.
+    wasm(),
+{{#if crate}}
+    crateWatch(),
+{{/if}}
+{{#if worker}}
+    workerProxy(),
+{{/if}}
+  ],
+  define: {
+    // Shows why the app failed to load on the page itself, unless built with
+    // `WASM_ERROR_OVERLAY=false`.
+    __ERROR_OVERLAY__: JSON.stringify(process.env.WASM_ERROR_OVERLAY !== "false"),
+  },
+  resolve: {
+    // Older wasm-pack output, like hello-wasm-pack's, imports its `.wasm`
+    // file without the extension.
+    extensions: [".mjs", ".js", ".json", ".wasm"],
+  },
+  build: {
+    // wasm-pack's output is instantiated with a top-level await.
+    target: "esnext",
+  },
+{{#if threads}}
+  // Wasm threads share memory through a `SharedArrayBuffer`, which browsers
+  // only allow on cross-origin isolated pages. Whatever serves the
+  // production build has to send these headers too.
+  server: {
+    headers: {
+      "Cross-Origin-Opener-Policy": "same-origin",
+      "Cross-Origin-Embedder-Policy": "require-corp",
+    },
+  },
+  preview: {
+    headers: {
+      "Cross-Origin-Opener-Policy": "same-origin",
+      "Cross-Origin-Embedder-Policy": "require-corp",
+    },
+  },
+  worker: {
+    // The thread pool's workers are ES modules.
+    format: "es",
+  },
+{{/if}}
+{{#if worker}}
+  worker: {
+    // worker.js is an ES module, and imports the wasm package like the page
+    // would otherwise.
+    format: "es",
+    plugins: () => [wasm()],
+  },
+{{/if}}
+});
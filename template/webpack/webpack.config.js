@@ -0,0 +1,150 @@
+{{#if pwa}}
+const CopyWebpackPlugin = require("copy-webpack-plugin");
+{{/if}}
+const HtmlWebpackPlugin = require("html-webpack-plugin");
+const path = require('path');
+const webpack = require("webpack");
+const { BundleAnalyzerPlugin } = require("webpack-bundle-analyzer");
+{{#if crate}}
+const { buildCrate, crateDir, isCrateSource } = require("./scripts/crate-dev.js");
+
+// Rebuilds the crate with wasm-pack when its sources change under `webpack
+// serve`. Cargo's errors fail the compilation, so the dev server shows them
+// in its overlay; once the crate builds, webpack sees its package change
+// and the page reloads.
+class CrateWatchPlugin {
+  apply(compiler) {
+    const srcDir = path.join(crateDir, "src");
+    // webpack reports a change inside a watched directory as a change to the
+    // directory itself, rather than to the file that changed.
+    const isCrateChange = file => {
+      const relative = path.relative(srcDir, file);
+      return isCrateSource(file) || !(relative.startsWith("..") || path.isAbsolute(relative));
+    };
+    let error = null;
+    compiler.hooks.watchRun.tapPromise("CrateWatchPlugin", async () => {
+      // Nothing has changed yet on the first run.
+      const changed = [...(compiler.modifiedFiles || [])];
+      if (changed.some(isCrateChange)) {
+        error = await buildCrate();
+      }
+    });
+    compiler.hooks.thisCompilation.tap("CrateWatchPlugin", compilation => {
+      compilation.contextDependencies.add(srcDir);
+      compilation.fileDependencies.add(path.join(crateDir, "Cargo.toml"));
+      if (error) {
+        compilation.errors.push(new webpack.WebpackError(`Building the crate failed:\n${error}`));
+      }
+    });
+  }
+}
+{{/if}}
+{{#if worker}}
+const { generateWorkerProxy } = require("./scripts/generate-worker-proxy.js");
+
+// Generates wasm-proxy.{{scriptExt}} from the wasm package's typings before
+// each build, so that it has a function for everything the package exports.
+class WorkerProxyPlugin {
+  apply(compiler) {
+    const generate = async () => generateWorkerProxy();
+    compiler.hooks.beforeRun.tapPromise("WorkerProxyPlugin", generate);
+    compiler.hooks.watchRun.tapPromise("WorkerProxyPlugin", generate);
+  }
+}
+{{/if}}
+
+// `webpack` and `webpack serve` build the fast development profile;
+// `webpack --mode production` builds minified, content-hashed files that
+// can be cached forever. `--env analyze` also writes the bundle analysis
+// to size-report/.
+module.exports = (env, argv) => {
+  const production = argv.mode === "production";
+{{#if crate}}
+  const serving = Boolean(env.WEBPACK_SERVE);
+{{/if}}
+
+  return {
+    entry: {
+      bootstrap: "./bootstrap.{{scriptExt}}",
+    },
+    output: {
+      path: path.resolve(__dirname, "dist"),
+      filename: production ? "[name].[contenthash].js" : "[name].js",
+      // For wasm modules, `[hash]` is a hash of the module's contents.
+      webassemblyModuleFilename: production ? "[hash].wasm" : "[id].module.wasm",
+      clean: production,
+    },
+    mode: production ? "production" : "development",
+    devtool: production ? "source-map" : "eval",
+{{#if typescript}}
+    module: {
+      rules: [
+        {
+          test: /\.ts$/,
+          loader: "ts-loader",
+        },
+      ],
+    },
+    resolve: {
+      // TypeScript modules import each other with the `.js` extension of the
+      // file they compile to.
+      extensionAlias: {
+        ".js": [".ts", ".js"],
+      },
+    },
+{{/if}}
+    experiments: {
+      // wasm-pack's output imports its `.wasm` file like any other module;
+      // webpack loads it asynchronously, behind the `import()` in bootstrap.js.
+      asyncWebAssembly: true,
+    },
+    plugins: [
+      // Adds the `<script>` tag for bootstrap.js, whose name changes with its
+      // contents in production.
+      new HtmlWebpackPlugin({
+        template: "index.html",
+      }),
+{{#if pwa}}
+      // The web app manifest, its icon and the service worker are served
+      // as they are.
+      new CopyWebpackPlugin({
+        patterns: [{ from: "public" }],
+      }),
+{{/if}}
+      new webpack.DefinePlugin({
+        // Shows why the app failed to load on the page itself, unless built
+        // with `WASM_ERROR_OVERLAY=false`.
+        __ERROR_OVERLAY__: JSON.stringify(process.env.WASM_ERROR_OVERLAY !== "false"),
+      }),
+{{#if crate}}
+      serving && new CrateWatchPlugin(),
+{{/if}}
+{{#if worker}}
+{{#if crate}}
+      // After CrateWatchPlugin, to see what the rebuilt crate exports.
+{{/if}}
+      new WorkerProxyPlugin(),
+{{/if}}
+      env.analyze && new BundleAnalyzerPlugin({
+        analyzerMode: "static",
+        reportFilename: path.resolve(__dirname, "size-report", "bundle.html"),
+        generateStatsFile: true,
+        statsFilename: path.resolve(__dirname, "size-report", "bundle-stats.json"),
+        openAnalyzer: false,
+      }),
+    ].filter(Boolean),
+    devServer: {
+      // Everything, index.html included, is served from webpack's output.
+      static: false,
+{{#if threads}}
+      // Wasm threads share memory through a `SharedArrayBuffer`, which
+      // browsers only allow on cross-origin isolated pages. Whatever serves
+      // the production build has to send these headers too.
+      headers: {
+        "Cross-Origin-Opener-Policy": "same-origin",
+        "Cross-Origin-Embedder-Policy": "require-corp",
+      },
+{{/if}}
+    },
+  };
+};
@@ -0,0 +1,72 @@
+// Generates `wasm-proxy.{{scriptExt}}`, which has a function for each function
+// `{{wasmPackage}}` exports. Each one sends the call to worker.{{scriptExt}}
+// and returns a promise of its result. The functions are read from the
+// package's typings, so it is generated again whenever the app is built, by
+// a plugin in {{#if bundler == "webpack"}}webpack.config.js{{else}}vite.config.js{{/if}}; `node scripts/generate-worker-proxy.js`
+// generates it on its own.
+//
+// Exported classes are left out: their instances live in the worker's memory
+// and can't be sent to the main thread.
+
+const fs = require("fs");
+const path = require("path");
+const { packageExports } = require("./dts");
+
+const PACKAGE = "{{wasmPackage}}";
+const OUTPUT = path.join(__dirname, "..", "wasm-proxy.{{scriptExt}}");
+
+// Writes wasm-proxy.{{scriptExt}} when it isn't up to date with the package's
+// typings. Throws when the package isn't installed or has no typings.
+function generateWorkerProxy() {
+  let pkgDir;
+  try {
+    pkgDir = path.dirname(require.resolve(`${PACKAGE}/package.json`, { paths: [path.join(__dirname, "..")] }));
+  } catch (e) {
+    throw new Error(`couldn't find \`${PACKAGE}\`; install the project's dependencies first`);
+  }
+
+  const declared = packageExports(pkgDir);
+  if (!declared) {
+    throw new Error(`\`${PACKAGE}\` has no typings to read its exports from`);
+  }
+
+  const lines = [
+    "// Generated by scripts/generate-worker-proxy.js, don't edit.",
+{{#if typescript}}
+    `import type * as wasm from ${JSON.stringify(PACKAGE)};`,
+{{/if}}
+    'import { call } from "./worker-bridge.js";',
+  ];
+  for (const fn of declared.functions) {
+    lines.push(
+      "",
+      `/** Runs \`${fn.name}(${fn.params}): ${fn.returns}\` in the worker. */`,
+{{#if typescript}}
+      `export function ${fn.name}(...args: Parameters<typeof wasm.${fn.name}>): ` +
+        `Promise<Awaited<ReturnType<typeof wasm.${fn.name}>>> {`,
+{{else}}
+      `export function ${fn.name}(...args) {`,
+{{/if}}
+      `  return call(${JSON.stringify(fn.name)}, args);`,
+      "}",
+    );
+  }
+
+  // Left alone when nothing changed, so that a watching bundler doesn't see
+  // it change and build again.
+  const source = lines.join("\n") + "\n";
+  if (!fs.existsSync(OUTPUT) || fs.readFileSync(OUTPUT, "utf8") !== source) {
+    fs.writeFileSync(OUTPUT, source);
+  }
+}
+
+if (require.main === module) {
+  try {
+    generateWorkerProxy();
+  } catch (e) {
+    console.error(e.message);
+    process.exit(1);
+  }
+}
+
+module.exports = { generateWorkerProxy };